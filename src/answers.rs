@@ -0,0 +1,194 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::{open_input, read_input, solutions, AocError, Part};
+
+pub const DEFAULT_MANIFEST: &str = "answers.txt";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Manifest {
+    entries: Vec<Expected>,
+}
+
+impl Manifest {
+    // every non-empty line that is not a '#' comment is "<day> <part> <input file> <answer>"
+    pub fn parse(text: &str) -> Result<Self, AocError> {
+        let mut entries = Vec::new();
+        for (line_idx, line) in text.lines().enumerate() {
+            let content = line.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() != 4 {
+                return Err(AocError::malformed(line_idx + 1, 1, format!("expected 4 fields, found {}", fields.len())));
+            }
+            let column = |field: &str| field.as_ptr() as usize - line.as_ptr() as usize + 1;
+            let day = fields[0]
+                .parse::<u8>()
+                .map_err(|_| AocError::malformed(line_idx + 1, column(fields[0]), format!("invalid day: {}", fields[0])))?;
+            let part = fields[1]
+                .parse::<Part>()
+                .map_err(|error| AocError::malformed(line_idx + 1, column(fields[1]), error))?;
+            entries.push(Expected { day, part, input: fields[2].to_string(), answer: fields[3].to_string() });
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn load(filename: &str) -> Result<Self, AocError> {
+        Self::parse(&read_input(open_input(filename)?)?)
+    }
+
+    pub fn entries(&self) -> &[Expected] {
+        &self.entries
+    }
+
+    pub fn lookup(&self, day: u8, part: Part, input: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.part == part && entry.input == input)
+            .map(|entry| entry.answer.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String, actual: String },
+    // there is no expected answer for this input yet
+    Missing { actual: String },
+    Error(AocError),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = format!("day {:02} part {} {}", self.day, self.part, self.input);
+        match &self.outcome {
+            Outcome::Pass => write!(f, "PASS    {}", label),
+            Outcome::Fail { expected, actual } => write!(f, "FAIL    {}: expected {}, got {}", label, expected, actual),
+            Outcome::Missing { actual } => write!(f, "MISSING {}: got {}", label, actual),
+            Outcome::Error(error) => write!(f, "ERROR   {}: {}", label, error),
+        }
+    }
+}
+
+// all the inputs of a day are named "dayNN<anything>.txt"
+fn inputs_of_day(input_dir: &str, day: u8) -> Result<Vec<String>, AocError> {
+    let prefix = format!("day{:02}", day);
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(input_dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.starts_with(&prefix) && name.ends_with(".txt") {
+            inputs.push(name);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+// run every solver (or just the one of the given day) against every one of its inputs and compare with the manifest; an
+// input of the manifest that is missing, or cannot be read, is an error of its own and the others are still checked
+pub fn verify(manifest: &Manifest, input_dir: &str, only_day: Option<u8>) -> Result<Vec<Check>, AocError> {
+    let mut checks = Vec::new();
+    for solution in solutions().iter().filter(|solution| only_day.is_none_or(|day| solution.day() == day)) {
+        let mut names = inputs_of_day(input_dir, solution.day())?;
+        names.extend(manifest.entries().iter().filter(|entry| entry.day == solution.day()).map(|entry| entry.input.clone()));
+        names.sort();
+        names.dedup();
+        for name in names {
+            let path = Path::new(input_dir).join(&name);
+            let input = open_input(&path.to_string_lossy()).and_then(read_input);
+            for part in solution.parts() {
+                let outcome = match (&input, manifest.lookup(solution.day(), *part, &name)) {
+                    (Err(error), _) => Outcome::Error(AocError::domain(format!("cannot read {}: {}", path.display(), error))),
+                    (Ok(input), expected) => match (solution.run(*part, input), expected) {
+                        (None, _) => Outcome::Error(AocError::domain(format!("day {} has no part {}", solution.day(), part))),
+                        (Some(Err(error)), _) => Outcome::Error(error),
+                        (Some(Ok(actual)), None) => Outcome::Missing { actual },
+                        (Some(Ok(actual)), Some(expected)) if actual == expected => Outcome::Pass,
+                        (Some(Ok(actual)), Some(expected)) => Outcome::Fail { expected: expected.to_string(), actual },
+                    },
+                };
+                checks.push(Check { day: solution.day(), part: *part, input: name.clone(), outcome });
+            }
+        }
+    }
+    Ok(checks)
+}
+
+// the answer recorded in the checked-in manifest, None while there is none (e.g. for a day just scaffolded)
+#[cfg(test)]
+pub(crate) fn recorded(day: u8, part: Part, input: &str) -> Option<String> {
+    let manifest = Manifest::load(DEFAULT_MANIFEST).unwrap();
+    manifest.lookup(day, part, input).map(str::to_string)
+}
+
+// the answer recorded in the checked-in manifest, for the tests of every day
+#[cfg(test)]
+pub(crate) fn expected(day: u8, part: Part, input: &str) -> String {
+    match recorded(day, part, input) {
+        Some(answer) => answer,
+        None => panic!("no expected answer for day {} part {} {}", day, part, input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_parse_and_lookup() {
+        let manifest = Manifest::parse("# comment\n\n1 1 day01a.txt 3\n1 2 day01a.txt 6\n").unwrap();
+        assert_eq!(manifest.entries().len(), 2);
+        assert_eq!(manifest.lookup(1, Part::Two, "day01a.txt"), Some("6"));
+        assert_eq!(manifest.lookup(1, Part::Two, "day01c.txt"), None);
+    }
+
+    #[test]
+    fn manifest_parse_reports_location() {
+        let error = Manifest::parse("1 1 day01a.txt 3\n1  3 day01a.txt 6").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 4: Invalid part: 3");
+        let error = Manifest::parse("1 1 day01a.txt").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 1: expected 4 fields, found 3");
+    }
+
+    #[test]
+    fn verify_reports_pass_fail_and_missing() {
+        let manifest = Manifest::parse("3 1 day03a.txt 357\n3 2 day03a.txt 42").unwrap();
+        let checks = verify(&manifest, "input", Some(3)).unwrap();
+        assert!(checks.iter().all(|check| check.day == 3));
+        let day03a = checks.iter().filter(|check| check.input == "day03a.txt").collect::<Vec<&Check>>();
+        assert!(matches!(day03a[0].outcome, Outcome::Pass));
+        assert!(matches!(&day03a[1].outcome, Outcome::Fail { expected, .. } if expected == "42"));
+        let day03b = checks.iter().find(|check| check.input == "day03b.txt").unwrap();
+        assert!(matches!(day03b.outcome, Outcome::Missing { .. }));
+    }
+
+    #[test]
+    fn verify_goes_on_past_a_missing_input() {
+        let manifest = Manifest::parse("3 1 day03-missing.txt 1\n3 1 day03a.txt 357").unwrap();
+        let checks = verify(&manifest, "input", Some(3)).unwrap();
+        let missing = checks.iter().filter(|check| check.input == "day03-missing.txt").collect::<Vec<&Check>>();
+        assert_eq!(missing.len(), 2);
+        assert!(matches!(&missing[0].outcome, Outcome::Error(error) if error.to_string().starts_with("cannot read input/day03-missing.txt: ")));
+        let day03a = checks.iter().find(|check| check.input == "day03a.txt").unwrap();
+        assert!(matches!(day03a.outcome, Outcome::Pass));
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::{open_input, read_input, AnySolution, AocError, Part};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    // there has to be at least one sample
+    pub fn of(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) { (sorted[middle - 1] + sorted[middle]) / 2 } else { sorted[middle] };
+        Self { min: sorted[0], median, max: sorted[sorted.len() - 1] }
+    }
+}
+
+// the timings of one part of one day, over a number of runs
+#[derive(Debug)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: Part,
+    pub runs: usize,
+    pub parse: Stats,
+    pub total: Stats,
+}
+
+impl Measurement {
+    // solving is whatever a whole run takes on top of the parsing
+    pub fn solve(&self) -> Duration {
+        self.total.median.saturating_sub(self.parse.median)
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {:02} part {}: median {:?} (min {:?}, max {:?}), parse {:?}, solve {:?} over {} runs",
+            self.day,
+            self.part,
+            self.total.median,
+            self.total.min,
+            self.total.max,
+            self.parse.median,
+            self.solve(),
+            self.runs
+        )
+    }
+}
+
+// returns None when the part is not solved, the warmup runs are not measured
+pub fn measure(solution: &dyn AnySolution, part: Part, input: &str, warmup: usize, runs: usize) -> Option<Result<Measurement, AocError>> {
+    // an error would only be measured over and over, so the first one ends the benchmark
+    for _ in 0..warmup {
+        if let Err(error) = solution.run(part, input)? {
+            return Some(Err(error));
+        }
+    }
+    let runs = runs.max(1);
+    let mut parse = Vec::with_capacity(runs);
+    let mut total = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        if let Err(error) = solution.parse(input) {
+            return Some(Err(error));
+        }
+        parse.push(start.elapsed());
+        let start = Instant::now();
+        if let Err(error) = solution.run(part, input)? {
+            return Some(Err(error));
+        }
+        total.push(start.elapsed());
+    }
+    Some(Ok(Measurement { day: solution.day(), part, runs, parse: Stats::of(&parse), total: Stats::of(&total) }))
+}
+
+// how the median of a part changed against the baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl Change {
+    // positive when it got slower
+    pub fn percent(&self) -> f64 {
+        (self.after.as_secs_f64() / self.before.as_secs_f64().max(1e-9) - 1.0) * 100.0
+    }
+
+    pub fn is_regression(&self, tolerance_percent: f64) -> bool {
+        self.percent() > tolerance_percent
+    }
+}
+
+// the medians of an earlier benchmark, to compare against
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: Vec<(u8, Part, Duration)>,
+}
+
+impl Baseline {
+    // every non-empty line that is not a '#' comment is "<day> <part> <median in nanoseconds>"
+    pub fn parse(text: &str) -> Result<Self, AocError> {
+        let mut medians = Vec::new();
+        for (line_idx, line) in text.lines().enumerate() {
+            let content = line.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() != 3 {
+                return Err(AocError::malformed(line_idx + 1, 1, format!("expected 3 fields, found {}", fields.len())));
+            }
+            let column = |field: &str| field.as_ptr() as usize - line.as_ptr() as usize + 1;
+            let day = fields[0]
+                .parse::<u8>()
+                .map_err(|_| AocError::malformed(line_idx + 1, column(fields[0]), format!("invalid day: {}", fields[0])))?;
+            let part = fields[1]
+                .parse::<Part>()
+                .map_err(|error| AocError::malformed(line_idx + 1, column(fields[1]), error))?;
+            let nanos = fields[2]
+                .parse::<u64>()
+                .map_err(|_| AocError::malformed(line_idx + 1, column(fields[2]), format!("invalid duration: {}", fields[2])))?;
+            medians.push((day, part, Duration::from_nanos(nanos)));
+        }
+        Ok(Self { medians })
+    }
+
+    pub fn load(filename: &str) -> Result<Self, AocError> {
+        Self::parse(&read_input(open_input(filename)?)?)
+    }
+
+    pub fn from_measurements(measurements: &[Measurement]) -> Self {
+        Self { medians: measurements.iter().map(|measurement| (measurement.day, measurement.part, measurement.total.median)).collect() }
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::from("# day part median (ns)\n");
+        for (day, part, median) in &self.medians {
+            text.push_str(&format!("{} {} {}\n", day, part, median.as_nanos()));
+        }
+        text
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), AocError> {
+        Ok(fs::write(filename, self.render())?)
+    }
+
+    pub fn lookup(&self, day: u8, part: Part) -> Option<Duration> {
+        self.medians
+            .iter()
+            .find(|(entry_day, entry_part, _)| *entry_day == day && *entry_part == part)
+            .map(|(_, _, median)| *median)
+    }
+
+    // None when the part was not benchmarked before
+    pub fn compare(&self, measurement: &Measurement) -> Option<Change> {
+        let before = self.lookup(measurement.day, measurement.part)?;
+        Some(Change { before, after: measurement.total.median })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|value| Duration::from_millis(*value)).collect()
+    }
+
+    #[test]
+    fn stats_of_samples() {
+        let stats = Stats::of(&millis(&[5, 1, 3]));
+        assert_eq!(stats, Stats { min: Duration::from_millis(1), median: Duration::from_millis(3), max: Duration::from_millis(5) });
+        let stats = Stats::of(&millis(&[4, 1, 2, 8]));
+        assert_eq!(stats.median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn measure_example_input() {
+        let solution = find(3).unwrap();
+        let measurement = measure(solution, Part::One, "987654321111111\n811111111111119", 1, 3).unwrap().unwrap();
+        assert_eq!((measurement.day, measurement.part, measurement.runs), (3, Part::One, 3));
+        assert!(measurement.total.min <= measurement.total.median && measurement.total.median <= measurement.total.max);
+        assert!(measure(solution, Part::One, "12a", 1, 3).unwrap().is_err());
+        assert!(measure(find(6).unwrap(), Part::Two, "1 2\n+ *", 1, 3).is_none());
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let measurement = Measurement {
+            day: 4,
+            part: Part::Two,
+            runs: 3,
+            parse: Stats::of(&millis(&[1])),
+            total: Stats::of(&millis(&[10, 12, 14])),
+        };
+        let baseline = Baseline::from_measurements(&[measurement]);
+        assert_eq!(baseline.render(), "# day part median (ns)\n4 2 12000000\n");
+        let parsed = Baseline::parse(&baseline.render()).unwrap();
+        assert_eq!(parsed, baseline);
+        assert_eq!(parsed.lookup(4, Part::Two), Some(Duration::from_millis(12)));
+        assert_eq!(parsed.lookup(4, Part::One), None);
+    }
+
+    #[test]
+    fn baseline_parse_reports_location() {
+        let error = Baseline::parse("1 1 100\n1 2 fast").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 5: invalid duration: fast");
+        let error = Baseline::parse("1 1").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 1: expected 3 fields, found 2");
+    }
+
+    #[test]
+    fn baseline_compare() {
+        let baseline = Baseline::parse("2 1 1000000").unwrap();
+        let measurement = Measurement {
+            day: 2,
+            part: Part::One,
+            runs: 1,
+            parse: Stats::of(&millis(&[0])),
+            total: Stats::of(&[Duration::from_micros(1250)]),
+        };
+        let change = baseline.compare(&measurement).unwrap();
+        assert!((change.percent() - 25.0).abs() < 1e-6);
+        assert!(change.is_regression(10.0));
+        assert!(!change.is_regression(30.0));
+        assert_eq!(Baseline::default().compare(&measurement), None);
+    }
+}
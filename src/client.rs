@@ -0,0 +1,312 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{AocError, Part};
+
+pub const YEAR: u16 = 2025;
+// the client speaks plain http only, there is no TLS here: it talks to the bundled stand-in server (see
+// `mock_server`), which listens here by default, and not to the real https site
+pub const DEFAULT_SERVER: &str = "http://127.0.0.1:8025";
+pub const SUBMIT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Server {
+    host: String,
+    port: u16,
+    prefix: String,
+}
+
+impl Server {
+    // "http://host[:port][/prefix]"
+    pub fn parse(url: &str) -> Result<Self, AocError> {
+        if url.starts_with("https://") {
+            return Err(AocError::domain(format!("https is not supported, the client only reaches the plain http mock server: {}", url)));
+        }
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| AocError::domain(format!("only http:// servers are supported: {}", url)))?;
+        let (authority, prefix) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], rest[idx..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().map_err(|_| AocError::domain(format!("invalid port in {}", url)))?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(AocError::domain(format!("missing host in {}", url)));
+        }
+        Ok(Self { host: host.to_string(), port, prefix: prefix.to_string() })
+    }
+}
+
+// a form value as application/x-www-form-urlencoded: letters, digits and "-._~" as they are, the other bytes as %XX
+pub fn form_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+// a minimal HTTP/1.1 exchange, one connection per request
+fn request(server: &Server, method: &str, path: &str, session: &str, form: Option<&str>) -> Result<Response, AocError> {
+    let mut stream = TcpStream::connect((server.host.as_str(), server.port))?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+
+    let mut message = format!(
+        "{} {}{} HTTP/1.1\r\nHost: {}:{}\r\nCookie: session={}\r\nUser-Agent: aoc_2025\r\nConnection: close\r\n",
+        method, server.prefix, path, server.host, server.port, session
+    );
+    if let Some(form) = form {
+        message.push_str(&format!("Content-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\n", form.len()));
+    }
+    message.push_str("\r\n");
+    message.push_str(form.unwrap_or(""));
+    stream.write_all(message.as_bytes())?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    parse_response(&String::from_utf8_lossy(&raw))
+}
+
+fn parse_response(raw: &str) -> Result<Response, AocError> {
+    // nothing the server could have meant, so there is no status to report
+    let invalid = || AocError::domain("invalid response from the server");
+    let (head, body) = raw.split_once("\r\n\r\n").ok_or_else(invalid)?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(invalid)?;
+    let length = head.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.eq_ignore_ascii_case("content-length") { value.trim().parse::<usize>().ok() } else { None }
+    });
+    let body = match length {
+        Some(length) if length <= body.len() => &body[..length],
+        _ => body,
+    };
+    Ok(Response { status, body: body.to_string() })
+}
+
+// the puzzle page tells the outcome of a submission in plain words
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooSoon,
+    WrongLevel,
+    Unknown(String),
+}
+
+impl Verdict {
+    pub fn from_page(page: &str) -> Self {
+        if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("That's not the right answer") {
+            Verdict::Incorrect
+        } else if page.contains("You gave an answer too recently") {
+            Verdict::TooSoon
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown(page.trim().to_string())
+        }
+    }
+}
+
+pub struct Client {
+    server: Server,
+    session: String,
+    cache_dir: PathBuf,
+    submit_interval: Duration,
+}
+
+impl Client {
+    pub fn new(server_url: &str, session: &str, cache_dir: impl Into<PathBuf>) -> Result<Self, AocError> {
+        if session.is_empty() {
+            return Err(AocError::domain("missing session cookie"));
+        }
+        Ok(Self {
+            server: Server::parse(server_url)?,
+            session: session.to_string(),
+            cache_dir: cache_dir.into(),
+            submit_interval: SUBMIT_INTERVAL,
+        })
+    }
+
+    pub fn with_submit_interval(mut self, submit_interval: Duration) -> Self {
+        self.submit_interval = submit_interval;
+        self
+    }
+
+    // the real input of a day is cached as input/dayNNb.txt, it is downloaded only once
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{:02}b.txt", day))
+    }
+
+    // returns the input and whether it was downloaded (rather than taken from the cache)
+    pub fn fetch_input(&self, day: u8) -> Result<(String, bool), AocError> {
+        let path = self.input_path(day);
+        if let Ok(input) = fs::read_to_string(&path)
+            && !input.is_empty()
+        {
+            return Ok((input, false));
+        }
+        let response = request(&self.server, "GET", &format!("/{}/day/{}/input", YEAR, day), &self.session, None)?;
+        if response.status != 200 {
+            return Err(AocError::Http { status: response.status, message: response.body.trim().to_string() });
+        }
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(&path, &response.body)?;
+        Ok((response.body, true))
+    }
+
+    // the time of the last submission is kept on disk, so the limit holds across runs
+    fn last_submission_path(&self) -> PathBuf {
+        self.cache_dir.join(".last_submission")
+    }
+
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Verdict, AocError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let last = fs::read_to_string(self.last_submission_path())
+            .ok()
+            .and_then(|text| text.trim().parse::<u64>().ok());
+        if let Some(last) = last {
+            let elapsed = now.saturating_sub(last);
+            if elapsed < self.submit_interval.as_secs() {
+                return Err(AocError::RateLimited { wait_secs: self.submit_interval.as_secs() - elapsed });
+            }
+        }
+
+        let form = format!("level={}&answer={}", form_encode(&part.to_string()), form_encode(answer.trim()));
+        let response = request(&self.server, "POST", &format!("/{}/day/{}/answer", YEAR, day), &self.session, Some(&form))?;
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(self.last_submission_path(), now.to_string())?;
+        if response.status != 200 {
+            return Err(AocError::Http { status: response.status, message: response.body.trim().to_string() });
+        }
+        Ok(Verdict::from_page(&response.body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, Puzzles};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_client_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn puzzles() -> Puzzles {
+        let mut puzzles = Puzzles::new("secret");
+        puzzles.inputs.insert(1, "L68\nL30\n".to_string());
+        puzzles.answers.insert((1, Part::One), "3".to_string());
+        puzzles
+    }
+
+    #[test]
+    fn server_parse() {
+        let server = Server::parse("http://localhost:8025/aoc/").unwrap();
+        assert_eq!(server, Server { host: "localhost".to_string(), port: 8025, prefix: "/aoc".to_string() });
+        let error = Server::parse("https://adventofcode.com").err().unwrap();
+        assert!(matches!(error, AocError::Domain(_)));
+        assert_eq!(error.to_string(), "https is not supported, the client only reaches the plain http mock server: https://adventofcode.com");
+        assert!(Server::parse("ftp://localhost").is_err());
+        assert!(Server::parse("http://:80").is_err());
+    }
+
+    #[test]
+    fn garbled_responses() {
+        assert_eq!(parse_response("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok!").unwrap().body, "ok");
+        for raw in ["", "garbage", "HTTP/1.1 two OK\r\n\r\nbody"] {
+            let error = parse_response(raw).err().unwrap();
+            assert!(matches!(error, AocError::Domain(_)));
+            assert_eq!(error.to_string(), "invalid response from the server");
+        }
+    }
+
+    #[test]
+    fn fetch_downloads_once_and_caches() {
+        let server = MockServer::start("127.0.0.1:0", puzzles()).unwrap();
+        let dir = temp_dir("fetch");
+        let client = Client::new(&server.url(), "secret", &dir).unwrap();
+
+        assert_eq!(client.fetch_input(1).unwrap(), ("L68\nL30\n".to_string(), true));
+        drop(server);
+        // the server is gone, but the input is cached by now
+        assert_eq!(client.fetch_input(1).unwrap(), ("L68\nL30\n".to_string(), false));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_with_wrong_session() {
+        let server = MockServer::start("127.0.0.1:0", puzzles()).unwrap();
+        let dir = temp_dir("session");
+        let client = Client::new(&server.url(), "guess", &dir).unwrap();
+        assert!(matches!(client.fetch_input(1), Err(AocError::Http { status: 400, .. })));
+        assert!(matches!(client.fetch_input(2), Err(AocError::Http { .. })));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn submit_is_rate_limited() {
+        let server = MockServer::start("127.0.0.1:0", puzzles()).unwrap();
+        let dir = temp_dir("submit");
+        let client = Client::new(&server.url(), "secret", &dir).unwrap();
+
+        assert_eq!(client.submit(1, Part::One, "3").unwrap(), Verdict::Correct);
+        assert!(matches!(client.submit(1, Part::One, "4"), Err(AocError::RateLimited { wait_secs }) if wait_secs > 0));
+
+        // without the local limit, the server enforces its own
+        let client = client.with_submit_interval(Duration::ZERO);
+        assert_eq!(client.submit(1, Part::One, "4").unwrap(), Verdict::TooSoon);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn submit_verdicts() {
+        let mut puzzles = puzzles();
+        puzzles.cooldown = Duration::ZERO;
+        let server = MockServer::start("127.0.0.1:0", puzzles).unwrap();
+        let dir = temp_dir("verdicts");
+        let client = Client::new(&server.url(), "secret", &dir).unwrap().with_submit_interval(Duration::ZERO);
+
+        assert_eq!(client.submit(1, Part::One, "4").unwrap(), Verdict::Incorrect);
+        assert_eq!(client.submit(1, Part::One, "3").unwrap(), Verdict::Correct);
+        assert_eq!(client.submit(1, Part::Two, "6").unwrap(), Verdict::WrongLevel);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn submit_encodes_the_form() {
+        assert_eq!(form_encode("-12 a&b=c+d/é"), "-12%20a%26b%3Dc%2Bd%2F%C3%A9");
+        let mut puzzles = puzzles();
+        puzzles.cooldown = Duration::ZERO;
+        puzzles.answers.insert((2, Part::One), "a&b=c d".to_string());
+        let server = MockServer::start("127.0.0.1:0", puzzles).unwrap();
+        let dir = temp_dir("encode");
+        let client = Client::new(&server.url(), "secret", &dir).unwrap().with_submit_interval(Duration::ZERO);
+        assert_eq!(client.submit(2, Part::One, "a&b=c d").unwrap(), Verdict::Correct);
+        assert_eq!(client.submit(2, Part::One, "a").unwrap(), Verdict::Incorrect);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,23 @@
-use std::fs;
-
-#[derive(PartialEq)]
-enum Direction {
+use std::io::BufRead;
+use std::iter::Enumerate;
+use std::slice;
+
+use crate::{open_input, AocError, Params, Solution};
+
+mod grammar;
+pub mod history;
+pub mod inverse;
+pub mod lock;
+pub mod render;
+pub mod stream;
+
+use grammar::Program;
+pub use history::{day01_history, DialHistory};
+pub use inverse::{day01_inverse, solve_inverse, Edit};
+pub use stream::{evaluate, Evaluation, Throughput};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
     Left,
     Right,
 }
@@ -15,10 +31,10 @@ impl std::fmt::Display for Direction {
     }
 }
 
-#[derive(PartialEq)]
-struct Operation {
-    direction: Direction,
-    distance: i16,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Operation {
+    pub direction: Direction,
+    pub distance: i64,
 }
 
 impl std::fmt::Display for Operation {
@@ -27,157 +43,587 @@ impl std::fmt::Display for Operation {
     }
 }
 
+// the header line of an input that sets up its own dial: "@dial size=N start=N"
+const HEADER: &str = "@dial";
+const DIAL_PARAMS: [&str; 2] = ["size", "start"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DialConfig {
+    pub size: i64,
+    pub start: i64,
+}
+
+impl Default for DialConfig {
+    fn default() -> Self {
+        Self { size: 100, start: 50 }
+    }
+}
+
+impl DialConfig {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let parsed = value.parse::<i64>().map_err(|_| format!("invalid value for {}: {}", key, value))?;
+        match key {
+            "size" => self.size = parsed,
+            "start" => self.start = parsed,
+            _ => return Err(format!("unknown dial setting: {}", key)),
+        }
+        Ok(())
+    }
+
+    fn validate(self) -> Result<Self, AocError> {
+        if self.size < 1 {
+            return Err(AocError::domain(format!("the dial needs at least one position, size is {}", self.size)));
+        }
+        if !(0..self.size).contains(&self.start) {
+            return Err(AocError::domain(format!("start {} is not on a dial of size {}", self.start, self.size)));
+        }
+        Ok(self)
+    }
+}
+
 struct Dial {
-    pos: i16,
-    max: i16,
+    pos: i64,
+    max: i64,
 }
 
 impl Dial {
-    fn new(pos: i16, max: i16) -> Self {
+    fn new(pos: i64, max: i64) -> Self {
         Self { pos, max }
     }
 
-    fn turn(&mut self, operation: &Operation) -> i16 {
-        let mut spills: i16 = 0;
-        let size: i16 = self.max + 1;
+    fn from_config(config: DialConfig) -> Self {
+        Self::new(config.start, config.size - 1)
+    }
+
+    // every step is checked, a position far up a huge dial plus a huge distance must not wrap around
+    fn turn(&mut self, operation: &Operation) -> Result<i64, AocError> {
+        let overflow = || AocError::overflow(format!("turning {} from position {}", operation, self.pos));
+        let mut spills: i64 = 0;
+        let size = self.max.checked_add(1).ok_or_else(overflow)?;
         // if the direction is left, subtract the distance from the position
-        if operation.direction == Direction::Left {
-            let increased = self.pos - operation.distance;
+        let pos = if operation.direction == Direction::Left {
+            let increased = self.pos.checked_sub(operation.distance).ok_or_else(overflow)?;
+            // passing (or landing on) zero for the first time, unless we start from it
             if self.pos != 0 && increased <= 0 {
                 spills += 1;
             }
-            spills += (increased / size).abs();
-            self.pos = increased % size;
-            while self.pos < 0 {
-                self.pos += size
-            }
+            // every further full round passes zero once more
+            spills = spills.checked_add((increased / size).checked_abs().ok_or_else(overflow)?).ok_or_else(overflow)?;
+            increased.rem_euclid(size)
         }
         // if the direction is right, add the distance to the position
         else {
-            let increased = self.pos + operation.distance;
+            let increased = self.pos.checked_add(operation.distance).ok_or_else(overflow)?;
             spills += increased / size;
-            self.pos = increased % size;
-        }
-        spills
+            increased.rem_euclid(size)
+        };
+        self.pos = pos;
+        Ok(spills)
     }
 
     fn inspect<F>(&self, f: F) -> bool
     where
-        F: Fn(i16) -> bool,
+        F: Fn(i64) -> bool,
     {
         f(self.pos)
     }
 }
 
-pub fn day01_part1(filename: &str) -> i16 {
-    let mut dial = Dial::new(50, 99);
+// where the dial shows zero during a turn: somewhere along the way, or at its very end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Crossing {
+    Passed,
+    Landed,
+}
 
-    // create a collection to keep all the operations
-    let mut operations = Vec::new();
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZeroEvent {
+    // the index of the operation in the sequence
+    pub step: usize,
+    pub direction: Direction,
+    // the number of clicks into the turn
+    pub click: i64,
+    pub crossing: Crossing,
+}
 
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
-    let lines = input.lines();
+impl std::fmt::Display for ZeroEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let crossing = match self.crossing {
+            Crossing::Passed => "passes",
+            Crossing::Landed => "lands on",
+        };
+        write!(f, "step {}: {} {} zero after {} clicks", self.step, self.direction, crossing, self.click)
+    }
+}
 
-    for line in lines {
-        // each line is in a format: "DN" where D just the first char and N is a remainder parsed as a number
-        let (direction, distance) = line.split_at(1);
+// every zero the dial shows while turning through the operations, a turn of many rounds yields its events one by one
+pub struct ZeroCrossings<'a> {
+    dial: Dial,
+    operations: Enumerate<slice::Iter<'a, Operation>>,
+    // the step being turned and the click of its next zero
+    current: Option<(usize, &'a Operation, i64)>,
+    failed: bool,
+}
 
-        if direction == "L" {
-            operations.push(Operation {
-                direction: Direction::Left,
-                distance: distance.parse::<i16>().unwrap(),
-            });
-        } else {
-            operations.push(Operation {
-                direction: Direction::Right,
-                distance: distance.parse::<i16>().unwrap(),
-            });
-        }
+impl<'a> ZeroCrossings<'a> {
+    pub fn new(operations: &'a [Operation], config: DialConfig) -> Self {
+        Self { dial: Dial::from_config(config), operations: operations.iter().enumerate(), current: None, failed: false }
     }
+}
 
-    // execute all the operations
-    let mut counter = 0;
-    for operation in operations {
-        dial.turn(&operation);
-        // inspect the position of the dial, if it's zero, increment the counter
-        if dial.inspect(|pos| pos == 0) {
-            counter += 1;
+impl Iterator for ZeroCrossings<'_> {
+    type Item = Result<ZeroEvent, AocError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((step, operation, click)) = self.current
+                && click <= operation.distance
+            {
+                // the next zero is a full round later, unless that is beyond any distance
+                self.current = click.checked_add(self.dial.max + 1).map(|next| (step, operation, next));
+                let crossing = if click == operation.distance { Crossing::Landed } else { Crossing::Passed };
+                return Some(Ok(ZeroEvent { step, direction: operation.direction, click, crossing }));
+            }
+            if self.failed {
+                return None;
+            }
+            let (step, operation) = self.operations.next()?;
+            // the first zero is as many clicks away as the position is from it, in the direction of the turn
+            let first = match operation.direction {
+                Direction::Left if self.dial.pos > 0 => self.dial.pos,
+                Direction::Left => self.dial.max + 1,
+                Direction::Right => self.dial.max + 1 - self.dial.pos,
+            };
+            if let Err(error) = self.dial.turn(operation) {
+                self.failed = true;
+                return Some(Err(error));
+            }
+            self.current = Some((step, operation, first));
         }
     }
-    counter
 }
 
-pub fn day01_part2(filename: &str) -> i16 {
-    let mut dial = Dial::new(50, 99);
-
+fn parse_operations(input: &str) -> Result<Vec<Operation>, AocError> {
     // create a collection to keep all the operations
     let mut operations = Vec::new();
+    // the instructions may define macros for the lines after them, see `grammar`
+    let mut program = Program::new();
 
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
-    let lines = input.lines();
+    for (line_idx, line) in input.lines().enumerate() {
+        // the header is not an operation, see `parse_input`
+        if line_idx == 0 && is_header(line, HEADER) {
+            continue;
+        }
+        operations.extend(program.parse_line(line, line_idx, 1)?);
+    }
+    Ok(operations)
+}
 
-    for line in lines {
-        // each line is in a format: "DN" where D just the first char and N is a remainder parsed as a number
-        let (direction, distance) = line.split_at(1);
+// a header is its name alone or followed by its settings, "@dialx" is not a header
+fn is_header(line: &str, header: &str) -> bool {
+    line.strip_prefix(header).is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
 
-        if direction == "L" {
-            operations.push(Operation {
-                direction: Direction::Left,
-                distance: distance.parse::<i16>().unwrap(),
-            });
-        } else {
-            operations.push(Operation {
-                direction: Direction::Right,
-                distance: distance.parse::<i16>().unwrap(),
-            });
-        }
+// the settings of a header line: "@dial size=N start=N", with the column each one is written at
+fn parse_header(line: &str) -> Result<(Params, Vec<(String, usize)>), AocError> {
+    let mut header = Params::default();
+    let mut columns = Vec::new();
+    for setting in line[HEADER.len()..].split_whitespace() {
+        let column = setting.as_ptr() as usize - line.as_ptr() as usize + 1;
+        let Some((key, value)) = setting.split_once('=') else {
+            return Err(AocError::malformed(1, column, format!("expected key=value, found {:?}", setting)));
+        };
+        header.set(key, value);
+        columns.push((key.to_string(), column));
     }
+    Ok((header, columns))
+}
 
-    // execute all the operations
-    let mut counter = 0;
-    for operation in operations {
-        let spills = dial.turn(&operation);
-        counter += spills;
-        println!("Position: {}, Operation: {}, Spills: {}", dial.pos, operation, spills);
+// the dial is the default one, unless the first line is a header, the parameters of the run win over both
+fn configure(first_line: Option<&str>, params: &Params) -> Result<DialConfig, AocError> {
+    let (header, columns) = match first_line.filter(|line| is_header(line, HEADER)) {
+        Some(line) => parse_header(line)?,
+        None => (Params::default(), Vec::new()),
+    };
+    let settings = header.merged(params);
+    let mut config = DialConfig::default();
+    for key in settings.keys() {
+        config.set(key, settings.get(key).unwrap_or_default()).map_err(|message| {
+            // a setting the parameters leave to the header is reported where the header writes it
+            match columns.iter().rfind(|(name, _)| name == key).filter(|_| params.get(key).is_none()) {
+                Some((_, column)) => AocError::malformed(1, *column, message),
+                None => AocError::domain(message),
+            }
+        })?;
+    }
+    config.validate()
+}
+
+fn parse_input(input: &str, params: &Params) -> Result<(DialConfig, Vec<Operation>), AocError> {
+    Ok((configure(input.lines().next(), params)?, parse_operations(input)?))
+}
+
+pub fn day01_part1_from_str(input: &str) -> Result<i64, AocError> {
+    day01_part1_with_params(input, &Params::default())
+}
+
+pub fn day01_part1_with_params(input: &str, params: &Params) -> Result<i64, AocError> {
+    Ok(evaluate(input.as_bytes(), params)?.part1)
+}
+
+pub fn day01_part1_from_reader<R: BufRead>(reader: R) -> Result<i64, AocError> {
+    Ok(evaluate(reader, &Params::default())?.part1)
+}
+
+pub fn day01_part1(filename: &str) -> Result<i64, AocError> {
+    day01_part1_from_reader(open_input(filename)?)
+}
+
+pub fn day01_part2_from_str(input: &str) -> Result<i64, AocError> {
+    day01_part2_with_params(input, &Params::default())
+}
+
+pub fn day01_part2_with_params(input: &str, params: &Params) -> Result<i64, AocError> {
+    Ok(evaluate(input.as_bytes(), params)?.part2)
+}
+
+// every time the dial shows zero while turning through the input, the audit trail of the part 2 count
+pub fn day01_zero_events(input: &str, params: &Params) -> Result<Vec<ZeroEvent>, AocError> {
+    let (config, operations) = parse_input(input, params)?;
+    ZeroCrossings::new(&operations, config).collect()
+}
+
+pub fn day01_part2_from_reader<R: BufRead>(reader: R) -> Result<i64, AocError> {
+    Ok(evaluate(reader, &Params::default())?.part2)
+}
+
+pub fn day01_part2(filename: &str) -> Result<i64, AocError> {
+    day01_part2_from_reader(open_input(filename)?)
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Answer = i64;
+
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Secret Entrance"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        stream::validate(input.as_bytes(), &Params::default())
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        day01_part1_from_str(input)
+    }
+
+    fn part2(&self, input: &str) -> Option<Result<Self::Answer, AocError>> {
+        Some(day01_part2_from_str(input))
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        &DIAL_PARAMS
+    }
+
+    fn part1_with(&self, input: &str, params: &Params) -> Result<Self::Answer, AocError> {
+        day01_part1_with_params(input, params)
+    }
+
+    fn part2_with(&self, input: &str, params: &Params) -> Option<Result<Self::Answer, AocError>> {
+        Some(day01_part2_with_params(input, params))
     }
-    counter
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::{with_tracer, MemorySink, Verbosity};
+    use crate::answers::expected;
+    use crate::read_input;
+    use crate::property::{check, find_failure, seed, shrink_towards_zero, Rng, Shrink};
+    use crate::Part;
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn day01_from_str() {
+        assert_eq!(day01_part1_from_str(EXAMPLE).unwrap(), 3);
+        assert_eq!(day01_part2_from_str(EXAMPLE).unwrap(), 6);
+    }
+
+    #[test]
+    fn day01_from_reader() {
+        assert_eq!(day01_part1_from_reader(EXAMPLE.as_bytes()).unwrap(), 3);
+    }
 
     #[test]
     fn day01_part1_a() {
-        let result = day01_part1("input/day01a.txt");
-        assert_eq!(result, 3);
+        let result = day01_part1("input/day01a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(1, Part::One, "day01a.txt"));
     }
 
     #[test]
     fn day01_part1_b() {
-        let result = day01_part1("input/day01b.txt");
-        assert_eq!(result, 1158);
+        let result = day01_part1("input/day01b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(1, Part::One, "day01b.txt"));
     }
 
     #[test]
     fn day01_part2_a() {
-        let result = day01_part2("input/day01a.txt");
-        assert_eq!(result, 6);
+        let result = day01_part2("input/day01a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(1, Part::Two, "day01a.txt"));
     }
 
     #[test]
     fn day01_part2_b() {
-        let result = day01_part2("input/day01b.txt");
-        assert_eq!(result, 6860);
+        let result = day01_part2("input/day01b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(1, Part::Two, "day01b.txt"));
+    }
+
+    #[test]
+    fn day01_traces_dial_turns() {
+        let sink = MemorySink::default();
+        with_tracer(Verbosity::Steps, Box::new(sink.clone()), || day01_part2_from_str("L68\nL30\nR48")).unwrap();
+        assert_eq!(
+            sink.events(),
+            vec![
+                "day01: Left 68 -> position 82, spills 1",
+                "day01: Left 30 -> position 52, spills 0",
+                "day01: Right 48 -> position 0, spills 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn day01_parse_reports_location() {
+        let error = parse_operations("L10\nR5\nRx5").err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 2: invalid distance: \"x5\"");
+        let error = parse_operations("L10\n\nX5").err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 1: unknown direction: 'X'");
+    }
+
+    #[test]
+    fn day01_zero_events_of_the_example() {
+        let events = day01_zero_events(EXAMPLE, &Params::default()).unwrap();
+        assert_eq!(events.len(), 6);
+        assert_eq!(events[0], ZeroEvent { step: 0, direction: Direction::Left, click: 50, crossing: Crossing::Passed });
+        assert_eq!(events[1].to_string(), "step 2: Right lands on zero after 48 clicks");
+        let landed = events.iter().filter(|event| event.crossing == Crossing::Landed).count();
+        assert_eq!(landed, 3);
+    }
+
+    #[test]
+    fn day01_zero_events_add_up_to_part2() {
+        let input = read_input(open_input("input/day01b.txt").unwrap()).unwrap();
+        let events = day01_zero_events(&input, &Params::default()).unwrap();
+        assert_eq!(events.len().to_string(), expected(1, Part::Two, "day01b.txt"));
+    }
+
+    #[test]
+    fn day01_zero_events_are_lazy() {
+        // a single position is zero on every click, there is no need to go through all of them
+        let operations = [Operation { direction: Direction::Right, distance: i64::MAX }];
+        let mut events = ZeroCrossings::new(&operations, DialConfig { size: 1, start: 0 });
+        let clicks = events.by_ref().take(3).map(|event| event.unwrap().click).collect::<Vec<i64>>();
+        assert_eq!(clicks, vec![1, 2, 3]);
+        // a turn that overflows ends the events with the error
+        let operations = [Operation { direction: Direction::Right, distance: 10 }, Operation { direction: Direction::Right, distance: i64::MAX }];
+        let events = ZeroCrossings::new(&operations, DialConfig::default()).collect::<Vec<_>>();
+        assert!(matches!(events.last(), Some(Err(AocError::Overflow(_)))));
+    }
+
+    #[test]
+    fn day01_traces_zero_events_in_detail() {
+        let sink = MemorySink::default();
+        with_tracer(Verbosity::Detail, Box::new(sink.clone()), || day01_part2_from_str("L68\nL30")).unwrap();
+        assert_eq!(sink.events()[0], "day01: step 0: Left passes zero after 50 clicks");
+    }
+
+    #[test]
+    fn day01_dial_from_header_and_params() {
+        // on a dial of 10 positions starting at 0, every turn here ends on zero
+        let input = "@dial size=10 start=0\nL10\nR20\nL30";
+        assert_eq!(day01_part1_from_str(input).unwrap(), 3);
+        assert_eq!(day01_part2_from_str(input).unwrap(), 6);
+        // the parameters of the run win over the header
+        let params = "start=5".parse::<Params>().unwrap();
+        assert_eq!(day01_part1_with_params(input, &params).unwrap(), 0);
+        let params = "size=100".parse::<Params>().unwrap();
+        assert_eq!(day01_part1_with_params(EXAMPLE, &params).unwrap(), 3);
+    }
+
+    #[test]
+    fn day01_dial_config_errors() {
+        let error = day01_part1_from_str("@dial size=10 start=x\nL10").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 15: invalid value for start: x");
+        let error = day01_part1_from_str("@dial size=10 radius=3\nL10").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 15: unknown dial setting: radius");
+        // the parameters replace a broken setting of the header
+        let params = "start=5".parse::<Params>().unwrap();
+        assert_eq!(day01_part1_with_params("@dial size=10 start=x\nL10", &params).unwrap(), 0);
+        // only the name followed by a space or the end of the line starts a header
+        for input in ["@dialx\nL10", "@dialsize=5\nL10"] {
+            let error = day01_part1_from_str(input).err().unwrap();
+            assert!(error.to_string().starts_with("line 1, column 1: "), "{}", error);
+        }
+        assert_eq!(day01_part1_from_str("@dial\nL50").unwrap(), 1);
+        let error = day01_part1_from_str("@dial size=0\nL10").err().unwrap();
+        assert_eq!(error.to_string(), "the dial needs at least one position, size is 0");
+        let params = "start=100".parse::<Params>().unwrap();
+        let error = day01_part1_with_params(EXAMPLE, &params).err().unwrap();
+        assert_eq!(error.to_string(), "start 100 is not on a dial of size 100");
+    }
+
+    // the reference for the arithmetic of `Dial::turn`: turning click by click, counting every zero on the way
+    fn turn_by_clicks(dial: &mut Dial, operation: &Operation) -> i64 {
+        let mut zeros = 0;
+        for _ in 0..operation.distance {
+            dial.pos = match operation.direction {
+                Direction::Left if dial.pos == 0 => dial.max,
+                Direction::Left => dial.pos - 1,
+                Direction::Right if dial.pos == dial.max => 0,
+                Direction::Right => dial.pos + 1,
+            };
+            if dial.pos == 0 {
+                zeros += 1;
+            }
+        }
+        zeros
+    }
+
+    fn clicks(size: i64, start: i64, direction: Direction, distance: i64) -> (i64, i64) {
+        let mut dial = Dial::from_config(DialConfig { size, start });
+        let zeros = turn_by_clicks(&mut dial, &Operation { direction, distance });
+        (dial.pos, zeros)
+    }
+
+    fn check_turns(size: i64, starts: impl Iterator<Item = i64> + Clone, distances: impl Iterator<Item = i64> + Clone) {
+        for start in starts {
+            for distance in distances.clone() {
+                for direction in [Direction::Left, Direction::Right] {
+                    let expected = clicks(size, start, direction, distance);
+                    let mut dial = Dial::from_config(DialConfig { size, start });
+                    let operation = Operation { direction, distance };
+                    let spills = dial.turn(&operation).unwrap();
+                    assert_eq!((dial.pos, spills), expected, "size {}, start {}, {}", size, start, operation);
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct TurnCase {
+        size: i64,
+        start: i64,
+        direction: Direction,
+        distance: i64,
+    }
+
+    impl Shrink for TurnCase {
+        fn shrink(&self) -> Vec<Self> {
+            let mut candidates = Vec::new();
+            // a smaller dial keeps the start on it
+            for size in shrink_towards_zero(self.size).into_iter().filter(|size| *size >= 1) {
+                candidates.push(TurnCase { size, start: self.start.min(size - 1), ..self.clone() });
+            }
+            for start in shrink_towards_zero(self.start) {
+                candidates.push(TurnCase { start, ..self.clone() });
+            }
+            for distance in shrink_towards_zero(self.distance) {
+                candidates.push(TurnCase { distance, ..self.clone() });
+            }
+            if self.direction == Direction::Right {
+                candidates.push(TurnCase { direction: Direction::Left, ..self.clone() });
+            }
+            candidates
+        }
+    }
+
+    // dials of every scale, from a single position to far more positions than any distance
+    fn random_turn(rng: &mut Rng) -> TurnCase {
+        let size = match rng.range(0, 3) {
+            0 => rng.range(1, 10),
+            1 => rng.range(1, 200),
+            2 => rng.range(1, 5000),
+            _ => rng.range(1, 1_000_000_000_000),
+        };
+        let direction = if rng.bool() { Direction::Left } else { Direction::Right };
+        // the reference is slow, a few rounds of the small dials are enough
+        let distance = rng.range(0, (size * 3 + 2).min(4000));
+        TurnCase { size, start: rng.range(0, size - 1), direction, distance }
+    }
+
+    fn turn_matches_clicks(case: &TurnCase) -> Result<(), String> {
+        let mut dial = Dial::from_config(DialConfig { size: case.size, start: case.start });
+        let spills = dial.turn(&Operation { direction: case.direction, distance: case.distance }).map_err(|error| error.to_string())?;
+        let expected = clicks(case.size, case.start, case.direction, case.distance);
+        if (dial.pos, spills) == expected {
+            Ok(())
+        } else {
+            Err(format!("turn gives position {} and {} zeros, clicking gives {:?}", dial.pos, spills, expected))
+        }
+    }
+
+    #[test]
+    fn day01_turn_matches_clicks_for_random_turns() {
+        check(seed(2025), 5000, random_turn, turn_matches_clicks);
+    }
+
+    #[test]
+    fn day01_turn_failures_shrink() {
+        // a deliberately wrong rule (no zero is ever passed going left) is caught and shrunk to its simplest case
+        let wrong = |case: &TurnCase| if case.direction == Direction::Left && clicks(case.size, case.start, case.direction, case.distance).1 > 0 { Err("missed".to_string()) } else { Ok(()) };
+        let (case, _) = find_failure(1, 1000, random_turn, wrong).unwrap();
+        assert_eq!((case.size, case.start, case.direction, case.distance), (1, 0, Direction::Left, 1));
+    }
+
+    #[test]
+    fn day01_turn_on_small_dials() {
+        for size in [1, 2, 3, 7, 100] {
+            check_turns(size, 0..size, 0..size * 3 + 2);
+        }
+    }
+
+    #[test]
+    fn day01_turn_on_a_dial_larger_than_the_distances() {
+        let size = 30000;
+        check_turns(size, [0, 1, 50, 15000, size - 1].into_iter(), [0, 1, 99, 1000, 29999, 30000, 30001].into_iter());
+    }
+
+    #[test]
+    fn day01_instructions_with_comments_repeats_and_macros() {
+        let input = "# the example, spelled differently\nL68\nL30\n\nR48 L5 R60\ndef back = L55 L1 L99\n$back R14 L82\n";
+        assert_eq!(day01_part2_from_str(input).unwrap(), day01_part2_from_str(EXAMPLE).unwrap());
+        // every one of ten full rounds passes zero
+        assert_eq!(day01_part2_from_str("(R50 R50)x10").unwrap(), 10);
+    }
+
+    #[test]
+    fn day01_distances_beyond_i16() {
+        assert_eq!(day01_part2_from_str("R40000\nL32768").unwrap(), 728);
+        let error = parse_operations("R99999999999999999999").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 2: distance out of range: 99999999999999999999");
+    }
+
+    #[test]
+    fn day01_overflow_is_reported() {
+        let error = day01_part2_from_str(&format!("R{}", i64::MAX)).err().unwrap();
+        assert_eq!(error.to_string(), format!("arithmetic overflow: turning Right {} from position 50", i64::MAX));
+        // a single position is passed on every click, twice the largest distance is too many zeros
+        let error = day01_part2_from_str(&format!("@dial size=1 start=0\nR{}\nR{}", i64::MAX, i64::MAX)).err().unwrap();
+        assert!(matches!(error, AocError::Overflow(what) if what == "the count of zeros"));
     }
 
     #[test]
     fn day01_left_no_spill() {
         let mut dial = Dial::new(50, 99);
         let operation = Operation {  direction: Direction::Left, distance: 30 };
-        let spills = dial.turn(&operation);
+        let spills = dial.turn(&operation).unwrap();
         assert_eq!(dial.pos, 20);
         assert_eq!(spills, 0);
     }
@@ -186,7 +632,7 @@ mod tests {
     fn day01_left_one_spill_end_at_zero() {
         let mut dial = Dial::new(50, 99);
         let operation = Operation {  direction: Direction::Left, distance: 50 };
-        let spills = dial.turn(&operation);
+        let spills = dial.turn(&operation).unwrap();
         assert_eq!(dial.pos, 0);
         assert_eq!(spills, 1);
     }
@@ -195,7 +641,7 @@ mod tests {
     fn day01_left_one_spill_end_below_zero() {
         let mut dial = Dial::new(50, 99);
         let operation = Operation {  direction: Direction::Left, distance: 60 };
-        let spills = dial.turn(&operation);
+        let spills = dial.turn(&operation).unwrap();
         assert_eq!(dial.pos, 90);
         assert_eq!(spills, 1);
     }
@@ -204,7 +650,7 @@ mod tests {
     fn day01_left_two_spills_end_zero() {
         let mut dial = Dial::new(50, 99);
         let operation = Operation {  direction: Direction::Left, distance: 150 };
-        let spills = dial.turn(&operation);
+        let spills = dial.turn(&operation).unwrap();
         assert_eq!(dial.pos, 0);
         assert_eq!(spills, 2);
     }
@@ -213,23 +659,53 @@ mod tests {
     fn day01_left_two_spills_end_below_zero() {
         let mut dial = Dial::new(50, 99);
         let operation = Operation {  direction: Direction::Left, distance: 160 };
-        let spills = dial.turn(&operation);
+        let spills = dial.turn(&operation).unwrap();
         assert_eq!(dial.pos, 90);
         assert_eq!(spills, 2);
     }
 
     #[test]
-    fn day01_right_no_spill() {}
+    fn day01_right_no_spill() {
+        let mut dial = Dial::new(50, 99);
+        let operation = Operation { direction: Direction::Right, distance: 30 };
+        let spills = dial.turn(&operation).unwrap();
+        assert_eq!(dial.pos, 80);
+        assert_eq!(spills, 0);
+    }
 
     #[test]
-    fn day01_right_one_spill_end_at_zero() {}
+    fn day01_right_one_spill_end_at_zero() {
+        let mut dial = Dial::new(50, 99);
+        let operation = Operation { direction: Direction::Right, distance: 50 };
+        let spills = dial.turn(&operation).unwrap();
+        assert_eq!(dial.pos, 0);
+        assert_eq!(spills, 1);
+    }
 
     #[test]
-    fn day01_right_one_spill_end_after_zero() {}
+    fn day01_right_one_spill_end_after_zero() {
+        let mut dial = Dial::new(50, 99);
+        let operation = Operation { direction: Direction::Right, distance: 60 };
+        let spills = dial.turn(&operation).unwrap();
+        assert_eq!(dial.pos, 10);
+        assert_eq!(spills, 1);
+    }
 
     #[test]
-    fn day01_right_two_spills_end_zero() {}
+    fn day01_right_two_spills_end_zero() {
+        let mut dial = Dial::new(50, 99);
+        let operation = Operation { direction: Direction::Right, distance: 150 };
+        let spills = dial.turn(&operation).unwrap();
+        assert_eq!(dial.pos, 0);
+        assert_eq!(spills, 2);
+    }
 
     #[test]
-    fn day01_right_two_spills_end_above_zero() {}
+    fn day01_right_two_spills_end_above_zero() {
+        let mut dial = Dial::new(50, 99);
+        let operation = Operation { direction: Direction::Right, distance: 160 };
+        let spills = dial.turn(&operation).unwrap();
+        assert_eq!(dial.pos, 10);
+        assert_eq!(spills, 2);
+    }
 }
@@ -0,0 +1,259 @@
+// the instructions, line by line:
+//
+//   line     := blank | "# comment" | "def NAME = sequence" | sequence
+//   sequence := element (" " element)* ["# comment"]
+//   element  := (turn | "(" sequence ")" | "$NAME") ["x" COUNT]
+//   turn     := ("L" | "R") DISTANCE
+//
+// e.g. "def wiggle = L10 R10", then "(R5 $wiggle)x3 L1", a name takes up the letters after it, so a macro
+// is repeated in a group: "($wiggle)x2"
+use std::collections::HashMap;
+use std::num::IntErrorKind;
+
+use super::{Direction, Operation};
+use crate::AocError;
+
+// a single line may not repeat itself into more operations than this
+pub const MAX_EXPANSION: usize = 1_000_000;
+// nor nest its groups deeper than this, every group is parsed by a call of its own
+pub const MAX_DEPTH: usize = 100;
+
+#[derive(Default)]
+pub struct Program {
+    macros: HashMap<String, Vec<Operation>>,
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    // byte offset into the text
+    offset: usize,
+    line_idx: usize,
+    // the column the text starts at
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(char) = self.peek() {
+            self.offset += char.len_utf8();
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    // the longest run of characters matching the predicate
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.offset;
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+        &self.text[start..self.offset]
+    }
+
+    fn error_at(&self, offset: usize, message: impl Into<String>) -> AocError {
+        AocError::malformed(self.line_idx + 1, self.column + offset, message)
+    }
+
+    fn error(&self, message: impl Into<String>) -> AocError {
+        self.error_at(self.offset, message)
+    }
+
+    // what ends an element: the end of the line, a space, a comment or the end of a group
+    fn at_boundary(&self) -> bool {
+        self.peek().is_none_or(|char| char.is_whitespace() || char == '#' || char == ')')
+    }
+}
+
+fn is_name(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+fn parse_number(cursor: &Cursor, digits: &str, offset: usize, what: &str) -> Result<i64, AocError> {
+    digits.parse::<i64>().map_err(|error| match error.kind() {
+        IntErrorKind::PosOverflow => cursor.error_at(offset, format!("{} out of range: {}", what, digits)),
+        _ => cursor.error_at(offset, format!("invalid {}: {:?}", what, digits)),
+    })
+}
+
+impl Program {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the operations of one line, which starts at the given (1-based) column of the input line
+    pub fn parse_line(&mut self, text: &str, line_idx: usize, column: usize) -> Result<Vec<Operation>, AocError> {
+        let mut cursor = Cursor { text, offset: 0, line_idx, column };
+        cursor.skip_spaces();
+        if cursor.text[cursor.offset..].starts_with("def ") {
+            self.parse_definition(&mut cursor)?;
+            return Ok(Vec::new());
+        }
+        self.parse_sequence(&mut cursor, 0)
+    }
+
+    fn parse_definition(&mut self, cursor: &mut Cursor) -> Result<(), AocError> {
+        cursor.offset += "def".len();
+        cursor.skip_spaces();
+        let start = cursor.offset;
+        let name = cursor.take_while(is_name);
+        if name.is_empty() {
+            return Err(cursor.error("expected a macro name"));
+        }
+        if self.macros.contains_key(name) {
+            return Err(cursor.error_at(start, format!("macro {} is already defined", name)));
+        }
+        cursor.skip_spaces();
+        if cursor.peek() != Some('=') {
+            return Err(cursor.error("expected '=' after the macro name"));
+        }
+        cursor.bump();
+        let operations = self.parse_sequence(cursor, 0)?;
+        self.macros.insert(name.to_string(), operations);
+        Ok(())
+    }
+
+    // the elements up to the end of the line (or of the group, at a depth above 0)
+    fn parse_sequence(&self, cursor: &mut Cursor, depth: usize) -> Result<Vec<Operation>, AocError> {
+        let in_group = depth > 0;
+        let mut operations = Vec::new();
+        loop {
+            cursor.skip_spaces();
+            match cursor.peek() {
+                None | Some('#') if in_group => return Err(cursor.error("missing ')'")),
+                None | Some('#') => return Ok(operations),
+                Some(')') if in_group => {
+                    cursor.bump();
+                    return Ok(operations);
+                }
+                Some(')') => return Err(cursor.error("unexpected ')'")),
+                Some(_) => {
+                    let element = self.parse_element(cursor, depth)?;
+                    if operations.len() + element.len() > MAX_EXPANSION {
+                        return Err(cursor.error(format!("the line expands to more than {} operations", MAX_EXPANSION)));
+                    }
+                    operations.extend(element);
+                }
+            }
+        }
+    }
+
+    fn parse_element(&self, cursor: &mut Cursor, depth: usize) -> Result<Vec<Operation>, AocError> {
+        let start = cursor.offset;
+        let operations = match cursor.peek() {
+            Some('(') if depth == MAX_DEPTH => return Err(cursor.error(format!("groups nested deeper than {}", MAX_DEPTH))),
+            Some('(') => {
+                cursor.bump();
+                self.parse_sequence(cursor, depth + 1)?
+            }
+            Some('$') => {
+                cursor.bump();
+                let name = cursor.take_while(is_name);
+                match self.macros.get(name) {
+                    Some(operations) => operations.clone(),
+                    None => return Err(cursor.error_at(start, format!("unknown macro: {}", name))),
+                }
+            }
+            Some(char @ ('L' | 'R')) => {
+                cursor.bump();
+                let direction = if char == 'L' { Direction::Left } else { Direction::Right };
+                let digits_offset = cursor.offset;
+                let digits = cursor.take_while(|char| char.is_ascii_digit());
+                if digits.is_empty() || !(cursor.at_boundary() || cursor.peek() == Some('x')) {
+                    // the whole rest of the token is shown, e.g. "x5" of "Rx5"
+                    cursor.take_while(|char| !(char.is_whitespace() || char == '#' || char == ')'));
+                    let token = &cursor.text[digits_offset..cursor.offset];
+                    return Err(cursor.error_at(digits_offset, format!("invalid distance: {:?}", token)));
+                }
+                vec![Operation { direction, distance: parse_number(cursor, digits, digits_offset, "distance")? }]
+            }
+            Some(char) => return Err(cursor.error(format!("unknown direction: {:?}", char))),
+            None => unreachable!("elements are only parsed before the end of the line"),
+        };
+
+        let operations = if cursor.peek() == Some('x') {
+            cursor.bump();
+            let count_offset = cursor.offset;
+            let digits = cursor.take_while(|char| char.is_ascii_digit());
+            let count = parse_number(cursor, digits, count_offset, "repeat count")? as usize;
+            if operations.len().saturating_mul(count) > MAX_EXPANSION {
+                return Err(cursor.error_at(count_offset, format!("the line expands to more than {} operations", MAX_EXPANSION)));
+            }
+            operations.repeat(count)
+        } else {
+            operations
+        };
+        if !cursor.at_boundary() {
+            return Err(cursor.error(format!("unexpected {:?}", cursor.peek().unwrap_or_default())));
+        }
+        Ok(operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Result<Vec<String>, AocError> {
+        let mut program = Program::new();
+        let mut operations = Vec::new();
+        for (line_idx, line) in lines.iter().enumerate() {
+            operations.extend(program.parse_line(line, line_idx, 1)?);
+        }
+        Ok(operations.iter().map(|operation| operation.to_string()).collect())
+    }
+
+    #[test]
+    fn grammar_turns_comments_and_blanks() {
+        assert_eq!(parse(&["L68", "", "# a comment", "R48 # and another", "  L5"]).unwrap(), vec!["Left 68", "Right 48", "Left 5"]);
+    }
+
+    #[test]
+    fn grammar_repeats_and_groups() {
+        assert_eq!(parse(&["R10x3"]).unwrap(), vec!["Right 10"; 3]);
+        assert_eq!(parse(&["(L1 R2)x2 L3"]).unwrap(), vec!["Left 1", "Right 2", "Left 1", "Right 2", "Left 3"]);
+        assert_eq!(parse(&["((L1)x2 R1)x2"]).unwrap().len(), 6);
+        assert_eq!(parse(&[&format!("{}R1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH))]).unwrap(), vec!["Right 1"]);
+        assert_eq!(parse(&["L1x0"]).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn grammar_macros() {
+        let operations = parse(&["def wiggle = L10 R10", "def twice = ($wiggle)x2", "R5 $twice", "$wiggle"]);
+        assert_eq!(operations.unwrap().len(), 7);
+        let operations = parse(&["def wiggle = L10 R10", "($wiggle)x2"]).unwrap();
+        assert_eq!(operations, vec!["Left 10", "Right 10", "Left 10", "Right 10"]);
+    }
+
+    #[test]
+    fn grammar_reports_location() {
+        // balanced or not, a deep nesting stops at the limit rather than running out of stack
+        let (nested, unbalanced) = (format!("{}R1{}", "(".repeat(50_000), ")".repeat(50_000)), "(".repeat(50_000));
+        let cases = [
+            (vec!["L10", "X10"], "line 2, column 1: unknown direction: 'X'"),
+            (vec!["L10", "Rx5"], "line 2, column 2: invalid distance: \"x5\""),
+            (vec!["L-5"], "line 1, column 2: invalid distance: \"-5\""),
+            (vec!["L10y"], "line 1, column 2: invalid distance: \"10y\""),
+            (vec!["L10x2y"], "line 1, column 6: unexpected 'y'"),
+            (vec!["(L10 R5"], "line 1, column 8: missing ')'"),
+            (vec!["L10)"], "line 1, column 4: unexpected ')'"),
+            (vec!["$nope"], "line 1, column 1: unknown macro: nope"),
+            (vec!["def a = L1", "def a = R1"], "line 2, column 5: macro a is already defined"),
+            (vec!["def a L1"], "line 1, column 7: expected '=' after the macro name"),
+            (vec!["R99999999999999999999"], "line 1, column 2: distance out of range: 99999999999999999999"),
+            (vec!["(L1 R1)x600000"], "line 1, column 9: the line expands to more than 1000000 operations"),
+            (vec!["L1", &nested], "line 2, column 101: groups nested deeper than 100"),
+            (vec![&unbalanced], "line 1, column 101: groups nested deeper than 100"),
+        ];
+        for (lines, message) in cases {
+            assert_eq!(parse(&lines).err().unwrap().to_string(), message, "{:?}", lines);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// the whole run of the dial at once, to ask about any step of it without turning the dial again: the position
+// after every step and, as prefix sums, the zeros up to every step
+use std::ops::Range;
+
+use super::{parse_input, Dial, DialConfig, Operation};
+use crate::{AocError, Params};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialHistory {
+    // positions[k] is where the dial is after the first k steps, positions[0] is the start
+    positions: Vec<i64>,
+    // zeros[k] is how often the dial showed zero during the first k steps, landings[k] how many of them ended on it
+    zeros: Vec<i64>,
+    landings: Vec<i64>,
+}
+
+impl DialHistory {
+    pub fn new(operations: &[Operation], config: DialConfig) -> Result<Self, AocError> {
+        let mut dial = Dial::from_config(config);
+        let mut positions = Vec::with_capacity(operations.len() + 1);
+        let mut zeros: Vec<i64> = Vec::with_capacity(operations.len() + 1);
+        let mut landings: Vec<i64> = Vec::with_capacity(operations.len() + 1);
+        positions.push(dial.pos);
+        zeros.push(0);
+        landings.push(0);
+        for operation in operations {
+            let spills = dial.turn(operation)?;
+            let zero_count = zeros[zeros.len() - 1].checked_add(spills).ok_or_else(|| AocError::overflow("the count of zeros"))?;
+            positions.push(dial.pos);
+            zeros.push(zero_count);
+            landings.push(landings[landings.len() - 1] + i64::from(dial.pos == 0));
+        }
+        Ok(Self { positions, zeros, landings })
+    }
+
+    // the number of steps
+    pub fn len(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // after the first `steps` steps, None beyond the last one
+    pub fn position(&self, steps: usize) -> Option<i64> {
+        self.positions.get(steps).copied()
+    }
+
+    fn window(prefix: &[i64], steps: Range<usize>) -> Option<i64> {
+        if steps.start > steps.end {
+            return None;
+        }
+        Some(prefix.get(steps.end)? - prefix[steps.start])
+    }
+
+    // every zero shown while turning the steps of the window (0-based, as the steps of the zero events)
+    pub fn zeros(&self, steps: Range<usize>) -> Option<i64> {
+        Self::window(&self.zeros, steps)
+    }
+
+    // the steps of the window that end on zero
+    pub fn landings(&self, steps: Range<usize>) -> Option<i64> {
+        Self::window(&self.landings, steps)
+    }
+
+    // the step that shows the nth (0-based) zero, None when there are not that many
+    pub fn nth_zero(&self, n: usize) -> Option<usize> {
+        // the first step after which more than n zeros were shown, the counts are never negative
+        let after = self.zeros.partition_point(|count| *count as u64 <= n as u64);
+        (after < self.zeros.len()).then(|| after - 1)
+    }
+}
+
+pub fn day01_history(input: &str, params: &Params) -> Result<DialHistory, AocError> {
+    let (config, operations) = parse_input(input, params)?;
+    DialHistory::new(&operations, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::{Crossing, Direction, ZeroCrossings, ZeroEvent};
+    use crate::property::{seed, Rng};
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn history_of_the_example() {
+        let history = day01_history(EXAMPLE, &Params::default()).unwrap();
+        assert_eq!(history.len(), 10);
+        assert_eq!((history.position(0), history.position(1), history.position(3), history.position(10)), (Some(50), Some(82), Some(0), Some(32)));
+        assert_eq!(history.position(11), None);
+        assert_eq!((history.zeros(0..10), history.landings(0..10)), (Some(6), Some(3)));
+        assert_eq!((history.zeros(2..5), history.landings(2..5)), (Some(2), Some(1)));
+        assert_eq!((history.zeros(4..4), history.zeros(0..11)), (Some(0), None));
+        assert_eq!((0..7).map(|n| history.nth_zero(n)).collect::<Vec<_>>(), vec![Some(0), Some(2), Some(4), Some(5), Some(7), Some(9), None]);
+    }
+
+    #[test]
+    fn history_agrees_with_the_zero_events() {
+        let mut rng = Rng::new(seed(18));
+        let operations = (0..500)
+            .map(|_| Operation { direction: if rng.bool() { Direction::Left } else { Direction::Right }, distance: rng.range(1, 250) })
+            .collect::<Vec<Operation>>();
+        let config = DialConfig { size: 37, start: 11 };
+        let history = DialHistory::new(&operations, config).unwrap();
+        let events = ZeroCrossings::new(&operations, config).map(Result::unwrap).collect::<Vec<ZeroEvent>>();
+        let steps = events.iter().map(|event| event.step).collect::<Vec<usize>>();
+        for (n, step) in steps.iter().enumerate() {
+            assert_eq!(history.nth_zero(n), Some(*step));
+        }
+        assert_eq!(history.nth_zero(steps.len()), None);
+        let landed = events.iter().filter(|event| event.crossing == Crossing::Landed).count() as i64;
+        assert_eq!(history.landings(0..500), Some(landed));
+        for _ in 0..200 {
+            let (a, b) = (rng.range(0, 500) as usize, rng.range(0, 500) as usize);
+            let window = a.min(b)..a.max(b);
+            let expected = steps.iter().filter(|step| window.contains(step)).count() as i64;
+            assert_eq!(history.zeros(window), Some(expected));
+        }
+    }
+}
@@ -0,0 +1,401 @@
+// the fewest instructions to change so that the dial ends up with a given count: going through the steps once, the
+// counts that can be reached with every number of edits so far at every position are kept as bitsets, then the
+// steps are walked back from the target to find the edits, recomputing the sets block by block from checkpoints
+use std::fmt;
+
+use super::{parse_input, Dial, DialConfig, Direction, Operation};
+use crate::{AocError, Params, Part};
+
+// the sets are as wide as the target and there is one per position
+pub const MAX_TARGET: i64 = 100_000;
+pub const MAX_SIZE: i64 = 10_000;
+// a state holds size * (target + 1) * (edits + 1) bits, and the checkpoints and a block of states are held at once, so
+// within both limits a single state can already be too large
+pub const MEMORY_BUDGET: u128 = 512 << 20;
+
+// one instruction replaced by another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edit {
+    pub step: usize,
+    pub before: Operation,
+    pub after: Operation,
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {}: {} -> {}", self.step, self.before, self.after)
+    }
+}
+
+// the counts 0..width, those beyond the target are of no use, as a count never goes down
+#[derive(Debug, Clone, PartialEq)]
+struct Counts {
+    words: Vec<u64>,
+    width: usize,
+}
+
+impl Counts {
+    fn new(width: usize) -> Self {
+        Self { words: vec![0; width.div_ceil(64)], width }
+    }
+
+    fn contains(&self, count: usize) -> bool {
+        count < self.width && self.words[count / 64] & (1 << (count % 64)) != 0
+    }
+
+    fn insert(&mut self, count: usize) {
+        if count < self.width {
+            self.words[count / 64] |= 1 << (count % 64);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    fn lowest(&self) -> Option<usize> {
+        let (index, word) = self.words.iter().enumerate().find(|(_, word)| **word != 0)?;
+        Some(index * 64 + word.trailing_zeros() as usize)
+    }
+
+    fn union_with(&mut self, other: &Counts) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    // every count moved up by `by`, those beyond the width are dropped
+    fn shifted(&self, by: usize) -> Counts {
+        let mut shifted = Counts::new(self.width);
+        let (words, bits) = (by / 64, by % 64);
+        for index in (words..self.words.len()).rev() {
+            let mut word = self.words[index - words] << bits;
+            if bits > 0 && index > words {
+                word |= self.words[index - words - 1] >> (64 - bits);
+            }
+            shifted.words[index] = word;
+        }
+        shifted.clear_beyond_width();
+        shifted
+    }
+
+    // every count from the lowest one on
+    fn closed_upwards(&self) -> Counts {
+        let mut closed = Counts::new(self.width);
+        if let Some(lowest) = self.lowest() {
+            for index in lowest / 64..closed.words.len() {
+                closed.words[index] = u64::MAX;
+            }
+            closed.words[lowest / 64] &= u64::MAX << (lowest % 64);
+            closed.clear_beyond_width();
+        }
+        closed
+    }
+
+    fn clear_beyond_width(&mut self) {
+        if !self.width.is_multiple_of(64) {
+            let last = self.words.len() - 1;
+            self.words[last] &= (1 << (self.width % 64)) - 1;
+        }
+    }
+}
+
+// state[edits][position]: the counts reachable so far
+type State = Vec<Vec<Counts>>;
+
+struct Solver<'a> {
+    operations: &'a [Operation],
+    size: i64,
+    part: Part,
+    width: usize,
+    max_edits: usize,
+}
+
+impl Solver<'_> {
+    // where every position goes on the step, and what it adds to the count
+    fn turns(&self, step: usize) -> Result<Vec<(usize, usize)>, AocError> {
+        (0..self.size)
+            .map(|pos| {
+                let mut dial = Dial::new(pos, self.size - 1);
+                let spills = dial.turn(&self.operations[step])?;
+                let gain = match self.part {
+                    Part::One => i64::from(dial.pos == 0),
+                    Part::Two => spills,
+                };
+                Ok((dial.pos as usize, gain.min(self.width as i64) as usize))
+            })
+            .collect()
+    }
+
+    fn initial(&self, start: i64) -> State {
+        let mut state = vec![vec![Counts::new(self.width); self.size as usize]; self.max_edits + 1];
+        state[0][start as usize].insert(0);
+        state
+    }
+
+    fn next(&self, state: &State, step: usize) -> Result<State, AocError> {
+        let turns = self.turns(step)?;
+        let mut next = vec![vec![Counts::new(self.width); self.size as usize]; self.max_edits + 1];
+        for edits in 0..=self.max_edits {
+            for (pos, counts) in state[edits].iter().enumerate().filter(|(_, counts)| !counts.is_empty()) {
+                let (to, gain) = turns[pos];
+                next[edits][to].union_with(&counts.shifted(gain));
+            }
+            if edits == 0 {
+                continue;
+            }
+            // an edited step goes anywhere, only ending on zero (and for part 2 passing it) adds to the count
+            let mut any = Counts::new(self.width);
+            for counts in &state[edits - 1][1..] {
+                any.union_with(counts);
+            }
+            let from_zero = &state[edits - 1][0];
+            let (elsewhere, onto_zero) = match self.part {
+                Part::One => {
+                    any.union_with(from_zero);
+                    (any.clone(), any.shifted(1))
+                }
+                // from a position other than zero, getting onto it passes it at least once, and every further
+                // round adds another
+                Part::Two => {
+                    let mut onto_zero = any.shifted(1);
+                    onto_zero.union_with(from_zero);
+                    any.union_with(from_zero);
+                    (any.closed_upwards(), onto_zero.closed_upwards())
+                }
+            };
+            next[edits][0].union_with(&onto_zero);
+            for counts in &mut next[edits][1..] {
+                counts.union_with(&elsewhere);
+            }
+        }
+        Ok(next)
+    }
+
+    // a replacement going from one position to the other, adding `gain` for part 2
+    fn replacement(&self, from: i64, to: i64, gain: i64) -> Operation {
+        let right = (to - from).rem_euclid(self.size);
+        // turning right onto zero lands on it once
+        let lands = (from + right) / self.size;
+        match self.part {
+            Part::Two if gain < lands => Operation { direction: Direction::Left, distance: from - to },
+            Part::Two => Operation { direction: Direction::Right, distance: right + (gain - lands) * self.size },
+            Part::One => Operation { direction: Direction::Right, distance: right },
+        }
+    }
+
+    // the step that led to (edits, position, count) from the state before it
+    fn step_back(&self, before: &State, step: usize, (edits, to, count): (usize, usize, usize)) -> Result<(usize, usize, usize, Option<Edit>), AocError> {
+        let turns = self.turns(step)?;
+        for (pos, (target, gain)) in turns.iter().enumerate() {
+            if *target == to && count >= *gain && before[edits][pos].contains(count - gain) {
+                return Ok((edits, pos, count - gain, None));
+            }
+        }
+        let onto_zero = usize::from(to == 0);
+        for (pos, counts) in before[edits - 1].iter().enumerate() {
+            let least = match self.part {
+                Part::One => onto_zero,
+                Part::Two => usize::from(to == 0 && pos != 0),
+            };
+            let previous = match self.part {
+                Part::One => (count >= least && counts.contains(count - least)).then_some(count - least),
+                Part::Two => (least..=count).map(|gain| count - gain).find(|previous| counts.contains(*previous)),
+            };
+            if let Some(previous) = previous {
+                let after = self.replacement(pos as i64, to as i64, (count - previous) as i64);
+                let edit = Edit { step, before: self.operations[step], after };
+                return Ok((edits - 1, pos, previous, Some(edit)));
+            }
+        }
+        unreachable!("every reachable count has a step leading to it")
+    }
+}
+
+// the fewest edits (at most `max_edits`) after which the dial shows `target` for the part, None when there are none
+pub fn solve_inverse(operations: &[Operation], config: DialConfig, part: Part, target: i64, max_edits: usize) -> Result<Option<Vec<Edit>>, AocError> {
+    if !(0..=MAX_TARGET).contains(&target) {
+        return Err(AocError::domain(format!("the target {} is not in 0..={}", target, MAX_TARGET)));
+    }
+    if config.size > MAX_SIZE {
+        return Err(AocError::domain(format!("a dial of size {} is larger than {}", config.size, MAX_SIZE)));
+    }
+    // the states before every block of steps, to walk back from
+    let block = operations.len().isqrt().max(1);
+    let states = (operations.len().div_ceil(block) + block) as u128;
+    let state_bytes = (max_edits as u128 + 1) * config.size as u128 * (target as u128 + 1).div_ceil(64) * 8;
+    if states * state_bytes > MEMORY_BUDGET {
+        return Err(AocError::domain(format!(
+            "a dial of size {} with target {} and {} edits needs about {} MiB, more than {} MiB",
+            config.size,
+            target,
+            max_edits,
+            (states * state_bytes) >> 20,
+            MEMORY_BUDGET >> 20
+        )));
+    }
+    let solver = Solver { operations, size: config.size, part, width: target as usize + 1, max_edits };
+
+    let mut checkpoints = Vec::new();
+    let mut state = solver.initial(config.start);
+    for step in 0..operations.len() {
+        if step % block == 0 {
+            checkpoints.push(state.clone());
+        }
+        state = solver.next(&state, step)?;
+    }
+    let target = target as usize;
+    let Some((edits, pos)) = (0..=max_edits).find_map(|edits| state[edits].iter().position(|counts| counts.contains(target)).map(|pos| (edits, pos)))
+    else {
+        return Ok(None);
+    };
+
+    let mut found = Vec::new();
+    let mut current = (edits, pos, target);
+    for (index, checkpoint) in checkpoints.into_iter().enumerate().rev() {
+        let steps = index * block..(index * block + block).min(operations.len());
+        let mut states = vec![checkpoint];
+        for step in steps.start..steps.end - 1 {
+            states.push(solver.next(&states[states.len() - 1], step)?);
+        }
+        for step in steps.rev() {
+            let (edits, pos, count, edit) = solver.step_back(&states[step % block], step, current)?;
+            found.extend(edit);
+            current = (edits, pos, count);
+        }
+    }
+    found.reverse();
+    Ok(Some(found))
+}
+
+pub fn day01_inverse(input: &str, params: &Params, part: Part, target: i64, max_edits: usize) -> Result<Option<Vec<Edit>>, AocError> {
+    let (config, operations) = parse_input(input, params)?;
+    solve_inverse(&operations, config, part, target, max_edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{seed, Rng};
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    fn count(operations: &[Operation], config: DialConfig, part: Part) -> i64 {
+        let mut dial = Dial::from_config(config);
+        let mut count = 0;
+        for operation in operations {
+            let spills = dial.turn(operation).unwrap();
+            count += match part {
+                Part::One => i64::from(dial.pos == 0),
+                Part::Two => spills,
+            };
+        }
+        count
+    }
+
+    fn edited(operations: &[Operation], edits: &[Edit]) -> Vec<Operation> {
+        let mut operations = operations.to_vec();
+        for edit in edits {
+            assert_eq!(operations[edit.step], edit.before);
+            assert_ne!(edit.before, edit.after);
+            operations[edit.step] = edit.after;
+        }
+        operations
+    }
+
+    // the fewest edits over every replacement with a distance below `distances`
+    fn brute_force(operations: &[Operation], config: DialConfig, part: Part, target: i64, max_edits: usize, distances: i64) -> Option<usize> {
+        let replacements = [Direction::Left, Direction::Right]
+            .into_iter()
+            .flat_map(|direction| (0..distances).map(move |distance| Operation { direction, distance }))
+            .collect::<Vec<Operation>>();
+        fn search(operations: &mut Vec<Operation>, from: usize, left: usize, replacements: &[Operation], reached: &dyn Fn(&[Operation]) -> bool) -> bool {
+            if reached(operations) {
+                return true;
+            }
+            if left == 0 {
+                return false;
+            }
+            for step in from..operations.len() {
+                let original = operations[step];
+                for replacement in replacements {
+                    operations[step] = *replacement;
+                    if search(operations, step + 1, left - 1, replacements, reached) {
+                        operations[step] = original;
+                        return true;
+                    }
+                }
+                operations[step] = original;
+            }
+            false
+        }
+        let reached = |operations: &[Operation]| count(operations, config, part) == target;
+        (0..=max_edits).find(|edits| search(&mut operations.to_vec(), 0, *edits, &replacements, &reached))
+    }
+
+    #[test]
+    fn counts_shift_and_close() {
+        let mut counts = Counts::new(130);
+        counts.insert(3);
+        counts.insert(63);
+        assert_eq!((0..130).filter(|count| counts.shifted(1).contains(*count)).collect::<Vec<usize>>(), vec![4, 64]);
+        assert_eq!((0..130).filter(|count| counts.shifted(66).contains(*count)).collect::<Vec<usize>>(), vec![69, 129]);
+        assert!(counts.shifted(127).is_empty());
+        let closed = counts.shifted(100).closed_upwards();
+        assert_eq!((closed.lowest(), closed.contains(129), closed.contains(130)), (Some(103), true, false));
+    }
+
+    #[test]
+    fn inverse_of_the_example() {
+        let (config, operations) = parse_input(EXAMPLE, &Params::default()).unwrap();
+        assert_eq!(day01_inverse(EXAMPLE, &Params::default(), Part::One, 3, 2).unwrap(), Some(Vec::new()));
+        for (part, target) in [(Part::One, 0), (Part::One, 5), (Part::Two, 2), (Part::Two, 40)] {
+            let edits = solve_inverse(&operations, config, part, target, 3).unwrap().unwrap();
+            assert_eq!(count(&edited(&operations, &edits), config, part), target, "{:?} {}", part, target);
+        }
+        // one edit can only get rid of so many zeros
+        assert_eq!(solve_inverse(&operations, config, Part::Two, 0, 1).unwrap(), None);
+        assert_eq!(solve_inverse(&operations, config, Part::One, 11, 10).unwrap(), None);
+    }
+
+    #[test]
+    fn inverse_matches_brute_force() {
+        let mut rng = Rng::new(seed(19));
+        let config = DialConfig { size: 5, start: 2 };
+        for _ in 0..20 {
+            let operations = (0..5)
+                .map(|_| Operation { direction: if rng.bool() { Direction::Left } else { Direction::Right }, distance: rng.range(0, 12) })
+                .collect::<Vec<Operation>>();
+            for part in [Part::One, Part::Two] {
+                let target = rng.range(0, 6);
+                let solved = solve_inverse(&operations, config, part, target, 2).unwrap();
+                if let Some(edits) = &solved {
+                    assert_eq!(count(&edited(&operations, edits), config, part), target);
+                }
+                // turning at most one round is every replacement for part 1, but not for part 2
+                let brute = brute_force(&operations, config, part, target, 2, if part == Part::One { 5 } else { 15 });
+                let solved = solved.map(|edits| edits.len());
+                match (part, brute) {
+                    (Part::One, _) => assert_eq!(solved, brute, "{:?} to {}", operations, target),
+                    (Part::Two, Some(brute)) => assert!(solved.is_some_and(|edits| edits <= brute), "{:?} to {}", operations, target),
+                    (Part::Two, None) => (),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_limits() {
+        let operations = [Operation { direction: Direction::Right, distance: 1 }];
+        let error = solve_inverse(&operations, DialConfig::default(), Part::One, MAX_TARGET + 1, 1).err().unwrap();
+        assert_eq!(error.to_string(), "the target 100001 is not in 0..=100000");
+        let error = solve_inverse(&operations, DialConfig { size: MAX_SIZE + 1, start: 0 }, Part::One, 1, 1).err().unwrap();
+        assert_eq!(error.to_string(), "a dial of size 10001 is larger than 10000");
+        // both within their limits, but 10000 * 100001 * 4 bits for each of the 2 states
+        let error = solve_inverse(&operations, DialConfig { size: MAX_SIZE, start: 0 }, Part::One, MAX_TARGET, 3).err().unwrap();
+        assert!(matches!(error, AocError::Domain(_)));
+        assert_eq!(error.to_string(), "a dial of size 10000 with target 100000 and 3 edits needs about 953 MiB, more than 512 MiB");
+        let error = solve_inverse(&operations, DialConfig { size: 100, start: 0 }, Part::Two, 10, usize::MAX).err().unwrap();
+        assert!(error.to_string().ends_with("more than 512 MiB"));
+    }
+}
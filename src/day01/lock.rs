@@ -0,0 +1,309 @@
+use std::fmt;
+use std::io::BufRead;
+
+use super::grammar::Program;
+use super::{is_header, Dial, DialConfig, Direction, Operation};
+use crate::trace::{self, Verbosity};
+use crate::{open_input, read_input, AocError};
+
+// the header line of a lock: "@lock sizes=100,10,10 starts=50,0,0 carry=wrap"
+const HEADER: &str = "@lock";
+
+// when a dial moves the next one: the next dial then turns one click in the same direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Carry {
+    // the dials turn on their own
+    None,
+    // on every full round, like an odometer: from the last position to zero going right, from zero to the last going left
+    Wrap,
+    // on every zero the dial passes or lands on, the way part 2 counts
+    Zero,
+}
+
+impl std::str::FromStr for Carry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Carry::None),
+            "wrap" => Ok(Carry::Wrap),
+            "zero" => Ok(Carry::Zero),
+            _ => Err(format!("invalid carry rule: {}", s)),
+        }
+    }
+}
+
+// the dials of a lock, the first one is dial 1, each one carries into the one after it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockConfig {
+    pub dials: Vec<DialConfig>,
+    pub carries: Vec<Carry>,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self { dials: vec![DialConfig::default()], carries: vec![Carry::None] }
+    }
+}
+
+fn parse_list<T: std::str::FromStr>(key: &str, value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|item| item.parse::<T>().map_err(|_| format!("invalid value for {}: {}", key, item)))
+        .collect()
+}
+
+impl LockConfig {
+    // sizes and starts have a value per dial, a single carry rule stands for all of the dials
+    fn parse_header(line: &str) -> Result<Self, AocError> {
+        let (mut sizes, mut starts, mut carries) = (None, None, None);
+        for setting in line[HEADER.len()..].split_whitespace() {
+            let column = setting.as_ptr() as usize - line.as_ptr() as usize + 1;
+            let malformed = |message: String| AocError::malformed(1, column, message);
+            let Some((key, value)) = setting.split_once('=') else {
+                return Err(malformed(format!("expected key=value, found {:?}", setting)));
+            };
+            match key {
+                "sizes" => sizes = Some(parse_list::<i64>(key, value).map_err(malformed)?),
+                "starts" => starts = Some(parse_list::<i64>(key, value).map_err(malformed)?),
+                "carry" => carries = Some(parse_list::<Carry>(key, value).map_err(malformed)?),
+                _ => return Err(malformed(format!("unknown lock setting: {}", key))),
+            }
+        }
+        let sizes = sizes.ok_or_else(|| AocError::malformed(1, 1, "the lock needs the sizes of its dials"))?;
+        let starts = starts.unwrap_or_else(|| vec![0; sizes.len()]);
+        if starts.len() != sizes.len() {
+            return Err(AocError::domain(format!("expected {} starts, found {}", sizes.len(), starts.len())));
+        }
+        let carries = match carries.as_deref() {
+            None => vec![Carry::Wrap; sizes.len()],
+            Some([carry]) => vec![*carry; sizes.len()],
+            Some(carries) if carries.len() == sizes.len() => carries.to_vec(),
+            Some(carries) => return Err(AocError::domain(format!("expected 1 or {} carry rules, found {}", sizes.len(), carries.len()))),
+        };
+        let dials = sizes
+            .into_iter()
+            .zip(starts)
+            .map(|(size, start)| DialConfig { size, start }.validate())
+            .collect::<Result<Vec<DialConfig>, AocError>>()?;
+        Ok(Self { dials, carries })
+    }
+}
+
+// an operation for one of the dials, "2:L30" (or just "L30" for the first one), a line for a dial can hold all
+// that a line of the puzzle can, e.g. "2:(L30 R5)x2"
+#[derive(Debug, PartialEq)]
+pub struct LockInstruction {
+    // 0-based
+    pub dial: usize,
+    pub operation: Operation,
+}
+
+impl fmt::Display for LockInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "dial {}: {}", self.dial + 1, self.operation)
+    }
+}
+
+fn parse_instructions(input: &str, dials: usize) -> Result<Vec<LockInstruction>, AocError> {
+    let mut instructions = Vec::new();
+    let mut program = Program::new();
+    for (line_idx, line) in input.lines().enumerate() {
+        if line_idx == 0 && is_header(line, HEADER) {
+            continue;
+        }
+        // only a number in front of the colon picks a dial, anything else is left to the grammar
+        let target = line
+            .split_once(':')
+            .filter(|(dial, _)| !dial.is_empty() && dial.chars().all(|char| char.is_ascii_digit()));
+        let (dial, operations) = match target {
+            Some((dial, rest)) => {
+                let number = dial
+                    .parse::<usize>()
+                    .map_err(|_| AocError::malformed(line_idx + 1, 1, format!("invalid dial: {:?}", dial)))?;
+                if number == 0 || number > dials {
+                    return Err(AocError::malformed(line_idx + 1, 1, format!("dial {} does not exist, the lock has {}", number, dials)));
+                }
+                (number - 1, program.parse_line(rest, line_idx, dial.len() + 2)?)
+            }
+            None => (0, program.parse_line(line, line_idx, 1)?),
+        };
+        instructions.extend(operations.into_iter().map(|operation| LockInstruction { dial, operation }));
+    }
+    Ok(instructions)
+}
+
+// how often a turn went from zero to the last position, the full rounds going left
+fn left_wraps(pos: i64, size: i64, distance: i64) -> Result<i64, AocError> {
+    let end = pos.checked_sub(distance).ok_or_else(|| AocError::overflow("the position of a dial"))?;
+    Ok(-end.div_euclid(size))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DialStats {
+    pub zeros: i64,
+    // into the next dial, the carries of the last dial are counted but lost
+    pub carries: i64,
+}
+
+pub struct Lock {
+    dials: Vec<Dial>,
+    carries: Vec<Carry>,
+    stats: Vec<DialStats>,
+}
+
+impl Lock {
+    pub fn new(config: &LockConfig) -> Self {
+        Self {
+            dials: config.dials.iter().map(|dial| Dial::from_config(*dial)).collect(),
+            carries: config.carries.clone(),
+            stats: vec![DialStats::default(); config.dials.len()],
+        }
+    }
+
+    pub fn positions(&self) -> Vec<i64> {
+        self.dials.iter().map(|dial| dial.pos).collect()
+    }
+
+    pub fn stats(&self) -> &[DialStats] {
+        &self.stats
+    }
+
+    // turn the dial, then the next one by its carries, and so on while there is something to carry
+    pub fn apply(&mut self, instruction: &LockInstruction) -> Result<(), AocError> {
+        let overflow = || AocError::overflow("the counts of a dial");
+        let direction = instruction.operation.direction;
+        let mut distance = instruction.operation.distance;
+        let mut index = instruction.dial;
+        while distance > 0 && index < self.dials.len() {
+            let dial = &mut self.dials[index];
+            let (pos, size) = (dial.pos, dial.max + 1);
+            let zeros = dial.turn(&Operation { direction, distance })?;
+            let carries = match (self.carries[index], direction) {
+                (Carry::None, _) => 0,
+                // going right, every full round ends on zero
+                (Carry::Wrap, Direction::Right) | (Carry::Zero, _) => zeros,
+                (Carry::Wrap, Direction::Left) => left_wraps(pos, size, distance)?,
+            };
+            let stats = &mut self.stats[index];
+            stats.zeros = stats.zeros.checked_add(zeros).ok_or_else(overflow)?;
+            stats.carries = stats.carries.checked_add(carries).ok_or_else(overflow)?;
+            distance = carries;
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LockReport {
+    pub positions: Vec<i64>,
+    pub stats: Vec<DialStats>,
+}
+
+impl fmt::Display for LockReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (position, stats)) in self.positions.iter().zip(&self.stats).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "dial {}: position {}, {} zeros, {} carries", index + 1, position, stats.zeros, stats.carries)?;
+        }
+        Ok(())
+    }
+}
+
+// without a header, the lock is the single dial of the puzzle
+pub fn day01_lock_from_str(input: &str) -> Result<LockReport, AocError> {
+    let config = match input.lines().next().filter(|line| is_header(line, HEADER)) {
+        Some(line) => LockConfig::parse_header(line)?,
+        None => LockConfig::default(),
+    };
+    let instructions = parse_instructions(input, config.dials.len())?;
+    let mut lock = Lock::new(&config);
+    for instruction in &instructions {
+        lock.apply(instruction)?;
+        trace::emit(Verbosity::Steps, || format!("day01: {} -> positions {:?}", instruction, lock.positions()));
+    }
+    Ok(LockReport { positions: lock.positions(), stats: lock.stats().to_vec() })
+}
+
+pub fn day01_lock_from_reader<R: BufRead>(reader: R) -> Result<LockReport, AocError> {
+    day01_lock_from_str(&read_input(reader)?)
+}
+
+pub fn day01_lock(filename: &str) -> Result<LockReport, AocError> {
+    day01_lock_from_reader(open_input(filename)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::day01_part2_from_str;
+
+    #[test]
+    fn lock_counts_like_an_odometer() {
+        // the first dial is the lowest digit
+        let report = day01_lock_from_str("@lock sizes=10,10,10\n1:R123\n").unwrap();
+        assert_eq!(report.positions, vec![3, 2, 1]);
+        assert_eq!(report.stats[0], DialStats { zeros: 12, carries: 12 });
+        // and borrowing going left, 123 - 4 = 119
+        let report = day01_lock_from_str("@lock sizes=10,10,10\nR123\nL4").unwrap();
+        assert_eq!(report.positions, vec![9, 1, 1]);
+        assert_eq!(report.stats[0], DialStats { zeros: 13, carries: 13 });
+        assert_eq!(report.stats[1], DialStats { zeros: 1, carries: 1 });
+    }
+
+    #[test]
+    fn lock_carry_rules() {
+        // landing on zero going left is a zero, but not a full round
+        let input = "@lock sizes=100,10 starts=50,0 carry=CARRY\nL50";
+        let report = day01_lock_from_str(&input.replace("CARRY", "wrap")).unwrap();
+        assert_eq!(report.positions, vec![0, 0]);
+        let report = day01_lock_from_str(&input.replace("CARRY", "zero")).unwrap();
+        assert_eq!(report.positions, vec![0, 9]);
+        let report = day01_lock_from_str(&input.replace("CARRY", "none,zero")).unwrap();
+        assert_eq!(report.positions, vec![0, 0]);
+        assert_eq!(report.stats[0], DialStats { zeros: 1, carries: 0 });
+    }
+
+    #[test]
+    fn lock_targets_dials() {
+        let report = day01_lock_from_str("@lock sizes=10,5 starts=0,4 carry=none\n2:R3\n1:L1\n2:L12").unwrap();
+        assert_eq!(report.positions, vec![9, 0]);
+        assert_eq!(report.to_string(), "dial 1: position 9, 0 zeros, 0 carries\ndial 2: position 0, 4 zeros, 0 carries");
+    }
+
+    #[test]
+    fn lock_lines_use_the_grammar() {
+        let report = day01_lock_from_str("@lock sizes=10,10\n# comment: not a dial\n2:(R1 R2)x2\n\n1:R5x2 # two rounds").unwrap();
+        assert_eq!(report.positions, vec![0, 7]);
+        let error = day01_lock_from_str("@lock sizes=10,10\n2:Q1").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 3: unknown direction: 'Q'");
+    }
+
+    #[test]
+    fn lock_without_header_is_the_puzzle_dial() {
+        const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        let report = day01_lock_from_str(EXAMPLE).unwrap();
+        assert_eq!(report.stats[0].zeros, day01_part2_from_str(EXAMPLE).unwrap());
+        assert_eq!(report.positions, vec![32]);
+    }
+
+    #[test]
+    fn lock_parse_reports_location() {
+        let error = day01_lock_from_str("@lock sizes=10,10\n3:R5").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: dial 3 does not exist, the lock has 2");
+        let error = day01_lock_from_str("@lock sizes=10,10\n2:Rx").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 4: invalid distance: \"x\"");
+        let error = day01_lock_from_str("@lock sizes=10,x").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 7: invalid value for sizes: x");
+        let error = day01_lock_from_str("@lock sizes=10 carry=spin").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 16: invalid value for carry: spin");
+        let error = day01_lock_from_str("@lock sizes=10,10 starts=1").err().unwrap();
+        assert_eq!(error.to_string(), "expected 2 starts, found 1");
+        // without a space after its name, the first line is an instruction, not a header
+        let error = day01_lock_from_str("@locksizes=10,10").err().unwrap();
+        assert!(error.to_string().starts_with("line 1, column 1: "), "{}", error);
+    }
+}
@@ -0,0 +1,191 @@
+// a run of the dial drawn step by step: a line of text per step for the terminal and a picture of the whole run as
+// SVG, both made from the turns of `Dial::turn`, so they show exactly what the answers count
+use std::f64::consts::TAU;
+use std::fmt::Write;
+
+use super::{parse_input, Dial, DialConfig, Direction, Operation};
+use crate::{AocError, Params};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub step: usize,
+    pub operation: Operation,
+    pub size: i64,
+    pub from: i64,
+    pub to: i64,
+    // the zeros shown during the step, and during the run up to it
+    pub zeros: i64,
+    pub total: i64,
+}
+
+impl Frame {
+    fn column(&self, pos: i64, columns: usize) -> usize {
+        (pos as i128 * columns as i128 / self.size as i128) as usize
+    }
+
+    // the dial unrolled into a strip: '-' where the pointer swept, '^' where it stopped and at the left end zero,
+    // '*' when the step showed it
+    pub fn strip(&self, width: usize) -> String {
+        let columns = width.min(self.size as usize).max(1);
+        let mut cells = vec!['.'; columns];
+        let (from, to) = (self.column(self.from, columns), self.column(self.to, columns));
+        let per_column = self.size / columns as i64;
+        if self.operation.distance >= self.size || (from == to && self.operation.distance > per_column) {
+            cells.fill('-');
+        } else if self.operation.distance > 0 {
+            let mut column = from;
+            loop {
+                cells[column] = '-';
+                if column == to {
+                    break;
+                }
+                column = match self.operation.direction {
+                    Direction::Right => (column + 1) % columns,
+                    Direction::Left => (column + columns - 1) % columns,
+                };
+            }
+        }
+        cells[0] = if self.zeros > 0 { '*' } else { '|' };
+        cells[to] = '^';
+        cells.into_iter().collect()
+    }
+
+    pub fn ascii(&self, width: usize) -> String {
+        format!(
+            "{:>5} {:<12} [{}] {} -> {}, zeros {} ({} in all)",
+            self.step,
+            self.operation.to_string(),
+            self.strip(width),
+            self.from,
+            self.to,
+            self.zeros,
+            self.total
+        )
+    }
+}
+
+pub fn frames(operations: &[Operation], config: DialConfig) -> Result<Vec<Frame>, AocError> {
+    let mut dial = Dial::from_config(config);
+    let mut total: i64 = 0;
+    let mut frames = Vec::with_capacity(operations.len());
+    for (step, operation) in operations.iter().enumerate() {
+        let from = dial.pos;
+        let zeros = dial.turn(operation)?;
+        total = total.checked_add(zeros).ok_or_else(|| AocError::overflow("the count of zeros"))?;
+        frames.push(Frame { step, operation: *operation, size: config.size, from, to: dial.pos, zeros, total });
+    }
+    Ok(frames)
+}
+
+pub fn day01_frames(input: &str, params: &Params) -> Result<(DialConfig, Vec<Frame>), AocError> {
+    let (config, operations) = parse_input(input, params)?;
+    Ok((config, frames(&operations, config)?))
+}
+
+const CENTER: f64 = 320.0;
+const FACE: f64 = 300.0;
+// the sweeps of the steps go round on rings from the inside out
+const INNER: f64 = 40.0;
+const OUTER: f64 = 270.0;
+
+// zero is at the top, turning right is clockwise
+fn point(size: i64, pos: i64, radius: f64) -> (f64, f64) {
+    let angle = TAU * pos as f64 / size as f64;
+    (CENTER + radius * angle.sin(), CENTER - radius * angle.cos())
+}
+
+// the face with its positions, the sweep of every step on its own ring, a red dot on the ring of every step that showed
+// zero and the pointer where the run ends
+pub fn svg(frames: &[Frame], config: DialConfig) -> String {
+    let mut svg = String::new();
+    let size = config.size;
+    let _ = writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"640\" height=\"640\" viewBox=\"0 0 640 640\">");
+    let _ = writeln!(
+        svg,
+        "<style>.face{{fill:none;stroke:#444;stroke-width:2}} .tick{{stroke:#888}} .zero-tick{{stroke:#d00;stroke-width:3}} \
+         .sweep{{fill:none;stroke-width:1.5}} .right{{stroke:#27c}} .left{{stroke:#e80}} .zero{{fill:#d00}} \
+         .pointer{{stroke:#000;stroke-width:3}}</style>"
+    );
+    let _ = writeln!(svg, "<circle class=\"face\" cx=\"{}\" cy=\"{}\" r=\"{}\"/>", CENTER, CENTER, FACE);
+    let tick_every = (size / 100).max(1);
+    for pos in (0..size).step_by(tick_every as usize) {
+        let (class, length) = if pos == 0 { ("zero-tick", 20.0) } else { ("tick", 10.0) };
+        let ((x1, y1), (x2, y2)) = (point(size, pos, FACE - length), point(size, pos, FACE));
+        let _ = writeln!(svg, "<line class=\"{}\" x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"/>", class, x1, y1, x2, y2);
+    }
+
+    for frame in frames {
+        let radius = INNER + (OUTER - INNER) * (frame.step + 1) as f64 / frames.len() as f64;
+        let class = match frame.operation.direction {
+            Direction::Right => "right",
+            Direction::Left => "left",
+        };
+        if frame.operation.distance >= size {
+            let _ = writeln!(svg, "<circle class=\"sweep {}\" cx=\"{}\" cy=\"{}\" r=\"{:.2}\"/>", class, CENTER, CENTER, radius);
+        } else if frame.operation.distance > 0 {
+            let ((x1, y1), (x2, y2)) = (point(size, frame.from, radius), point(size, frame.to, radius));
+            let large = i32::from(2 * frame.operation.distance > size);
+            let clockwise = i32::from(frame.operation.direction == Direction::Right);
+            let _ = writeln!(
+                svg,
+                "<path class=\"sweep {}\" d=\"M {:.2} {:.2} A {:.2} {:.2} 0 {} {} {:.2} {:.2}\"/>",
+                class, x1, y1, radius, radius, large, clockwise, x2, y2
+            );
+        }
+        if frame.zeros > 0 {
+            let (x, y) = point(size, 0, radius);
+            let _ = writeln!(
+                svg,
+                "<circle class=\"zero\" cx=\"{:.2}\" cy=\"{:.2}\" r=\"3\"><title>step {}: {} zeros</title></circle>",
+                x, y, frame.step, frame.zeros
+            );
+        }
+    }
+
+    let end = frames.last().map_or(config.start, |frame| frame.to);
+    let (x, y) = point(size, end, FACE - 30.0);
+    let _ = writeln!(svg, "<line class=\"pointer\" x1=\"{}\" y1=\"{}\" x2=\"{:.2}\" y2=\"{:.2}\"/>", CENTER, CENTER, x, y);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::day01_part2_from_str;
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn frames_match_the_answer() {
+        let (_, frames) = day01_frames(EXAMPLE, &Params::default()).unwrap();
+        assert_eq!(frames.len(), 10);
+        assert_eq!(frames[9].total, day01_part2_from_str(EXAMPLE).unwrap());
+        assert_eq!((frames[0].from, frames[0].to, frames[0].zeros), (50, 82, 1));
+    }
+
+    #[test]
+    fn frames_as_ascii() {
+        let (_, frames) = day01_frames("@dial size=10 start=5\nR3\nL9\nR20\nR0", &Params::default()).unwrap();
+        let lines = frames.iter().map(|frame| frame.ascii(10)).collect::<Vec<String>>();
+        assert_eq!(lines[0], "    0 Right 3      [|....---^.] 5 -> 8, zeros 0 (0 in all)");
+        assert_eq!(lines[1], "    1 Left 9       [*--------^] 8 -> 9, zeros 1 (1 in all)");
+        assert_eq!(lines[2], "    2 Right 20     [*--------^] 9 -> 9, zeros 2 (3 in all)");
+        assert_eq!(lines[3], "    3 Right 0      [|........^] 9 -> 9, zeros 0 (3 in all)");
+        // a dial larger than the width is scaled down
+        let (_, frames) = day01_frames(EXAMPLE, &Params::default()).unwrap();
+        assert_eq!(frames[2].strip(20), "^.........----------");
+    }
+
+    #[test]
+    fn svg_of_the_example() {
+        let (config, frames) = day01_frames(EXAMPLE, &Params::default()).unwrap();
+        let svg = svg(&frames, config);
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("class=\"sweep ").count(), 10);
+        // one dot for each of the 6 steps that show zero (of 6 zeros)
+        assert_eq!(svg.matches("class=\"zero\"").count(), 6);
+        assert_eq!(svg.matches("class=\"tick\"").count(), 99);
+        assert!(svg.contains("<title>step 2: 1 zeros</title>"));
+    }
+}
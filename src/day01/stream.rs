@@ -0,0 +1,210 @@
+// both parts of day 1 in a single pass over a reader: only the current line, its operations and the macros are
+// kept, so the memory is bounded by the longest line (at most `MAX_LINE` bytes, expanding to at most
+// `MAX_EXPANSION` operations), not by the length of the input
+use std::fmt;
+use std::io::{self, BufRead, Read};
+use std::slice;
+use std::time::{Duration, Instant};
+
+use super::grammar::Program;
+use super::{configure, is_header, Dial, DialConfig, Operation, ZeroCrossings, ZeroEvent, HEADER};
+use crate::trace::{self, Verbosity};
+use crate::{AocError, Params};
+
+// the longest line read, newline aside: a file without newlines is not read whole
+pub const MAX_LINE: usize = 1 << 20;
+
+// how much of the input went through, and how fast
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Throughput {
+    pub lines: u64,
+    pub bytes: u64,
+    pub operations: u64,
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    pub fn per_second(&self, count: u64) -> f64 {
+        count as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} lines, {} bytes, {} operations in {:?} ({:.0} lines/s, {:.1} MB/s, {:.0} ops/s)",
+            self.lines,
+            self.bytes,
+            self.operations,
+            self.elapsed,
+            self.per_second(self.lines),
+            self.per_second(self.bytes) / 1e6,
+            self.per_second(self.operations)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Evaluation {
+    // the turns ending on zero
+    pub part1: i64,
+    // every zero on the way
+    pub part2: i64,
+    pub throughput: Throughput,
+}
+
+// the turns of one line are traced as they happen, in detail with every zero they show before the turn itself
+fn turn_line(dial: &mut Dial, evaluation: &mut Evaluation, line: &[Operation]) -> Result<(), AocError> {
+    for operation in line {
+        let step = evaluation.throughput.operations as usize;
+        if trace::enabled(Verbosity::Detail) {
+            let config = DialConfig { size: dial.max + 1, start: dial.pos };
+            for event in ZeroCrossings::new(slice::from_ref(operation), config) {
+                let event = ZeroEvent { step, ..event? };
+                trace::emit(Verbosity::Detail, || format!("day01: {}", event));
+            }
+        }
+        let spills = dial.turn(operation)?;
+        evaluation.part2 = evaluation.part2.checked_add(spills).ok_or_else(|| AocError::overflow("the count of zeros"))?;
+        // inspect the position of the dial, if it's zero, increment the counter
+        if dial.inspect(|pos| pos == 0) {
+            evaluation.part1 += 1;
+        }
+        evaluation.throughput.operations += 1;
+        trace::emit(Verbosity::Steps, || format!("day01: {} -> position {}, spills {}", operation, dial.pos, spills));
+    }
+    Ok(())
+}
+
+// the operations of every line of the reader with the dial they turn, as soon as the line is read: the one reader
+// behind both the parts and the parse of day 1, returning how many lines and bytes it went through
+fn read_lines<R: BufRead>(
+    mut reader: R,
+    params: &Params,
+    mut each: impl FnMut(&mut Dial, &[Operation]) -> Result<(), AocError>,
+) -> Result<(u64, u64), AocError> {
+    let (mut lines, mut total_bytes) = (0, 0);
+    let mut program = Program::new();
+    let mut dial: Option<Dial> = None;
+    // the one buffer every line is read into
+    let mut line = Vec::new();
+
+    for line_idx in 0.. {
+        line.clear();
+        let bytes = reader.by_ref().take(MAX_LINE as u64 + 1).read_until(b'\n', &mut line)?;
+        if bytes == 0 {
+            break;
+        }
+        if bytes > MAX_LINE && !line.ends_with(b"\n") {
+            return Err(AocError::malformed(line_idx + 1, MAX_LINE + 1, format!("line is longer than {} bytes", MAX_LINE)));
+        }
+        lines += 1;
+        total_bytes += bytes as u64;
+        let text = std::str::from_utf8(&line)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8"))?
+            .trim_end_matches(['\n', '\r']);
+
+        // the dial is known once the first line is, which may be its header
+        let dial = match &mut dial {
+            Some(dial) => dial,
+            None => dial.insert(Dial::from_config(configure(Some(text), params)?)),
+        };
+        if line_idx == 0 && is_header(text, HEADER) {
+            continue;
+        }
+        let operations = program.parse_line(text, line_idx, 1)?;
+        each(dial, &operations)?;
+    }
+    // an empty input still has its dial checked
+    if dial.is_none() {
+        configure(None, params)?;
+    }
+    Ok((lines, total_bytes))
+}
+
+pub fn evaluate<R: BufRead>(reader: R, params: &Params) -> Result<Evaluation, AocError> {
+    let start = Instant::now();
+    let mut evaluation = Evaluation { part1: 0, part2: 0, throughput: Throughput::default() };
+    let (lines, bytes) = read_lines(reader, params, |dial, operations| turn_line(dial, &mut evaluation, operations))?;
+    evaluation.throughput = Throughput { lines, bytes, elapsed: start.elapsed(), ..evaluation.throughput };
+    Ok(evaluation)
+}
+
+// the input read as `evaluate` reads it, without turning the dial
+pub fn validate<R: BufRead>(reader: R, params: &Params) -> Result<(), AocError> {
+    read_lines(reader, params, |_, _| Ok(())).map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    // a long input made up on the fly, as a generator of stress inputs would write it
+    struct Synthetic {
+        lines: u64,
+        pending: Vec<u8>,
+    }
+
+    impl Read for Synthetic {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() {
+                if self.lines == 0 {
+                    return Ok(0);
+                }
+                self.lines -= 1;
+                let line = if self.lines.is_multiple_of(2) { "L150\n" } else { "R150\n" };
+                self.pending.extend_from_slice(line.as_bytes());
+            }
+            let count = buf.len().min(self.pending.len());
+            buf[..count].copy_from_slice(&self.pending[..count]);
+            self.pending.drain(..count);
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn evaluate_both_parts_at_once() {
+        let evaluation = evaluate("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n".as_bytes(), &Params::default()).unwrap();
+        assert_eq!((evaluation.part1, evaluation.part2), (3, 6));
+        assert_eq!((evaluation.throughput.lines, evaluation.throughput.bytes, evaluation.throughput.operations), (10, 38, 10));
+    }
+
+    #[test]
+    fn evaluate_a_long_stream() {
+        // from 50, R150 passes zero and lands on it, L150 passes it once more on the way back to 50
+        let evaluation = evaluate(BufReader::new(Synthetic { lines: 200_000, pending: Vec::new() }), &Params::default()).unwrap();
+        assert_eq!((evaluation.part1, evaluation.part2), (100_000, 300_000));
+        assert_eq!(evaluation.throughput.lines, 200_000);
+        assert_eq!(evaluation.throughput.bytes, 1_000_000);
+        assert!(evaluation.throughput.to_string().starts_with("200000 lines, 1000000 bytes, 200000 operations in "));
+    }
+
+    #[test]
+    fn evaluate_header_params_and_errors() {
+        let evaluation = evaluate("@dial size=10 start=0\r\nL10\r\n(R20)x2".as_bytes(), &Params::default()).unwrap();
+        assert_eq!((evaluation.part1, evaluation.part2, evaluation.throughput.operations), (3, 5, 3));
+        let params = "start=5".parse::<Params>().unwrap();
+        assert_eq!(evaluate("@dial size=10 start=0\nL10".as_bytes(), &params).unwrap().part1, 0);
+        let error = evaluate("L10\nR5\nRx5\nL10".as_bytes(), &Params::default()).err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 2: invalid distance: \"x5\"");
+        let params = "size=0".parse::<Params>().unwrap();
+        assert!(evaluate("".as_bytes(), &params).is_err());
+        assert!(matches!(evaluate(&[b'L', 0xff, b'\n'][..], &Params::default()), Err(AocError::Io(_))));
+        // the parse goes through the same lines
+        assert!(validate("@dial size=10 start=0\nL10\n(R20)x2".as_bytes(), &Params::default()).is_ok());
+        assert_eq!(validate("L10\nR5\nRx5".as_bytes(), &Params::default()).err().unwrap().to_string(), "line 3, column 2: invalid distance: \"x5\"");
+    }
+
+    #[test]
+    fn evaluate_bounds_the_line_length() {
+        // a line of exactly `MAX_LINE` bytes still goes through, with or without its newline
+        let line = format!("{}R1", " ".repeat(MAX_LINE - 2));
+        assert_eq!(evaluate(format!("L50\n{}\n", line).as_bytes(), &Params::default()).unwrap().throughput.operations, 2);
+        assert_eq!(evaluate(format!("L50\n{}", line).as_bytes(), &Params::default()).unwrap().throughput.operations, 2);
+        // a generated input without newlines is cut short instead of read whole
+        let error = evaluate("R1 ".repeat(MAX_LINE).as_bytes(), &Params::default()).err().unwrap();
+        assert_eq!(error.to_string(), format!("line 1, column {}: line is longer than {} bytes", MAX_LINE + 1, MAX_LINE));
+    }
+}
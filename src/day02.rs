@@ -1,105 +1,870 @@
-use std::fs;
-
-pub fn day02_part1(filename: &str) -> i64 {
-    let mut result = 0;
-    // read the input file - it will be just one line
-    let input = fs::read_to_string(filename).unwrap();
-    // split the lines into pairs of numbers, separated by "," - the pair elements are separated by "-"
-    let pairs = input.split(",").map(|pair| pair.split("-").map(|num| num.parse::<i64>().unwrap()).collect::<Vec<i64>>()).collect::<Vec<Vec<i64>>>();
-    // iterate all the pairs
-    for pair in pairs {
-        // iterate from the first number to the second number, by one
-        for num in pair[0]..=pair[1] {
-            // if num has the uneven number of digits, continue to the next number
-            if num.to_string().len() % 2 != 0 {
-                continue;
-            }
-            // split the number into two halves
-            let half = num.to_string().len() / 2;
-            let first_half = num.to_string().chars().take(half).collect::<String>();
-            let second_half = num.to_string().chars().skip(half).collect::<String>();
-            // check if the first half is equal to the second half
-            if first_half == second_half {
-                result += num;
+use std::collections::{btree_set, BTreeSet};
+use std::fmt;
+use std::io::BufRead;
+use std::num::IntErrorKind;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::{open_input, read_input, AocError, Params, Solution};
+
+// the IDs and their sums, an ID may have up to 39 digits
+pub type Id = u128;
+
+fn parse_pairs(input: &str) -> Result<Vec<Vec<Id>>, AocError> {
+    let mut pairs = Vec::new();
+    // split the line into pairs of numbers, separated by "," - the pair elements are separated by "-"
+    let mut column = 1;
+    for pair in input.trim_end().split(",") {
+        let Some((start, end)) = pair.split_once("-") else {
+            return Err(AocError::malformed(1, column, format!("expected a range, found {:?}", pair)));
+        };
+        let mut numbers = Vec::new();
+        for (offset, num) in [(0, start), (start.len() + 1, end)] {
+            let parsed = num.parse::<Id>().map_err(|error| match error.kind() {
+                IntErrorKind::PosOverflow => AocError::malformed(1, column + offset, format!("number out of range: {:?}", num)),
+                _ => AocError::malformed(1, column + offset, format!("invalid number: {:?}", num)),
+            })?;
+            numbers.push(parsed);
+        }
+        if numbers[0] > numbers[1] {
+            return Err(AocError::malformed(1, column, format!("range starts after it ends: {}", pair)));
+        }
+        pairs.push(numbers);
+        column += pair.len() + 1;
+    }
+    Ok(pairs)
+}
+
+// which IDs are invalid: a block of digits repeated a number of times within bounds (part 1 is exactly twice, part 2
+// at least twice), the same digits backwards as forwards, or a repetition with a single digit changed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    Repeated { min: u32, max: u32 },
+    Palindrome,
+    NearRepeat,
+}
+
+impl Rule {
+    pub const PART1: Rule = Rule::Repeated { min: 2, max: 2 };
+    pub const PART2: Rule = Rule::Repeated { min: 2, max: u32::MAX };
+}
+
+// x2 is a block exactly twice, x2+ at least twice, x2-4 two to four times
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rule: {}", s);
+        match s {
+            "palindrome" => return Ok(Rule::Palindrome),
+            "near-repeat" => return Ok(Rule::NearRepeat),
+            _ => {}
+        }
+        let count = s.strip_prefix('x').ok_or_else(invalid)?;
+        let parse = |count: &str| count.parse::<u32>().map_err(|_| invalid());
+        let (min, max) = if let Some(min) = count.strip_suffix('+') {
+            (parse(min)?, u32::MAX)
+        } else if let Some((min, max)) = count.split_once('-') {
+            (parse(min)?, parse(max)?)
+        } else {
+            let count = parse(count)?;
+            (count, count)
+        };
+        // a block on its own is not a repetition
+        if min < 2 || max < min {
+            return Err(invalid());
+        }
+        Ok(Rule::Repeated { min, max })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Repeated { min, max: u32::MAX } => write!(f, "x{}+", min),
+            Rule::Repeated { min, max } if min == max => write!(f, "x{}", min),
+            Rule::Repeated { min, max } => write!(f, "x{}-{}", min, max),
+            Rule::Palindrome => write!(f, "palindrome"),
+            Rule::NearRepeat => write!(f, "near-repeat"),
+        }
+    }
+}
+
+// the base the digits of the IDs are written in for the rules, from 2 to 36; the IDs of the input are always decimal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Base(u32);
+
+impl Base {
+    pub const DECIMAL: Base = Base(10);
+
+    pub fn new(radix: u32) -> Result<Self, AocError> {
+        if !(2..=36).contains(&radix) {
+            return Err(AocError::domain(format!("base must be from 2 to 36, found {}", radix)));
+        }
+        Ok(Base(radix))
+    }
+
+    pub fn radix(&self) -> u32 {
+        self.0
+    }
+
+    fn pow(&self, exponent: u32) -> Id {
+        Id::pow(Id::from(self.0), exponent)
+    }
+
+    fn digits(&self, num: Id) -> u32 {
+        num.checked_ilog(Id::from(self.0)).unwrap_or(0) + 1
+    }
+
+    // the digits of a number, the most significant first
+    fn digit_values(&self, num: Id) -> Vec<u32> {
+        let mut values = Vec::with_capacity(self.digits(num) as usize);
+        let mut rest = num;
+        loop {
+            values.push((rest % Id::from(self.0)) as u32);
+            rest /= Id::from(self.0);
+            if rest == 0 {
+                break;
             }
         }
+        values.reverse();
+        values
+    }
+
+    // the number of the digits, None when it does not fit
+    fn number(&self, values: &[u32]) -> Option<Id> {
+        values.iter().try_fold(0, |num: Id, value| num.checked_mul(Id::from(self.0))?.checked_add(Id::from(*value)))
+    }
+
+    // the number written in the base, in lower case beyond 9
+    pub fn format(&self, num: Id) -> String {
+        self.digit_values(num).into_iter().filter_map(|value| char::from_digit(value, self.0)).collect()
+    }
+
+    // the IDs of lo..=hi that have `length` digits
+    fn within(&self, lo: Id, hi: Id, length: u32) -> (Id, Id) {
+        let end = Id::checked_pow(Id::from(self.0), length).map_or(Id::MAX, |power| power - 1);
+        (lo.max(self.pow(length - 1)), hi.min(end))
     }
-    return result
 }
 
+impl FromStr for Base {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u32>().ok().and_then(|radix| Base::new(radix).ok()).ok_or_else(|| format!("invalid base: {}", s))
+    }
+}
 
-fn compare_texts_in_array(texts: &Vec<String>) -> bool {
-    let to_compare = texts[0].clone();
-    for text in texts {
-        if *text != to_compare {
-            return false;
+impl fmt::Display for Base {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// the block lengths an ID of `length` digits splits into evenly
+fn divisors(length: u32) -> impl Iterator<Item = u32> {
+    (1..length).filter(move |block| length.is_multiple_of(*block))
+}
+
+// a block and how often it is repeated, or the one digit a repetition has changed, as an ID matches a rule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+    // e.g. 2x3 for 121212
+    Repeated { block: u32, repeats: u32 },
+    Palindrome,
+    // the digit is counted from 1 on the left, e.g. 1x4 but digit 3 for 1121
+    NearRepeat { block: u32, repeats: u32, digit: u32 },
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Repeated { block, repeats } => write!(f, "{}x{}", block, repeats),
+            Pattern::Palindrome => write!(f, "palindrome"),
+            Pattern::NearRepeat { block, repeats, digit } => write!(f, "{}x{} but digit {}", block, repeats, digit),
         }
     }
-    return true;
 }
 
-pub fn day02_part2(filename: &str) -> i64 {
-    let mut result = 0;
-    // read the input file - it will be just one line
-    let input = fs::read_to_string(filename).unwrap();
-    // split the lines into pairs of numbers, separated by "," - the pair elements are separated by "-"
-    let pairs = input.split(",").map(|pair| pair.split("-").map(|num| num.parse::<i64>().unwrap()).collect::<Vec<i64>>()).collect::<Vec<Vec<i64>>>();
-    // iterate all the pairs
-    for pair in pairs {
-        // iterate from the first number to the second number, by one
-        'the_outer_loop: for num in pair[0]..=pair[1] {
-            let mut divisor = 1;
-            let num_stringized = num.to_string();
-            let num_len = num_stringized.len();
-            // maximum divisor is a half of the number of digits, rounded down to the nearest integer
-            let max_divisor = num_len / 2;
-
-            'the_inner_loop: while divisor <= max_divisor {
-                if num_len % divisor != 0 {
-                    divisor += 1;
-                    continue 'the_inner_loop;
+// the one digit keeping the digits from being a repetition of `block` digits, if only one does: with two blocks it is
+// taken to be in the second, with more it is the one differing from all the others in its column
+fn near_repeat(values: &[u32], block: u32) -> Option<Pattern> {
+    let (width, repeats) = (block as usize, values.len() / block as usize);
+    let mut changed = None;
+    for column in 0..width {
+        let column_values = (0..repeats).map(|index| values[index * width + column]).collect::<Vec<u32>>();
+        let expected = match column_values[..] {
+            [first, second, ..] if repeats == 2 || first == second => first,
+            [first, _, third, ..] if first == third => first,
+            [_, second, ..] => second,
+            _ => return None,
+        };
+        let mut differing = (0..repeats).filter(|index| column_values[*index] != expected);
+        match (differing.next(), differing.next()) {
+            (None, _) => continue,
+            (Some(index), None) if changed.is_none() => {
+                // the repetition would start with a zero
+                if index == 0 && column == 0 && expected == 0 {
+                    return None;
                 }
+                changed = Some(index * width + column);
+            }
+            _ => return None,
+        }
+    }
+    changed.map(|position| Pattern::NearRepeat { block, repeats: repeats as u32, digit: position as u32 + 1 })
+}
+
+// every pattern of the rule an ID matches, shortest block first, and none when it is valid: the one check behind the
+// generated IDs and their breakdown
+pub fn detect(id: Id, rule: Rule, base: Base) -> Vec<Pattern> {
+    let values = base.digit_values(id);
+    let length = values.len() as u32;
+    let periodic = |block: u32| values.iter().zip(&values[block as usize..]).all(|(a, b)| a == b);
+    match rule {
+        Rule::Repeated { min, max } => divisors(length)
+            .filter(|block| (min..=max).contains(&(length / block)) && periodic(*block))
+            .map(|block| Pattern::Repeated { block, repeats: length / block })
+            .collect(),
+        // a single digit is no more a palindrome than it is a repetition
+        Rule::Palindrome if length > 1 && values.iter().eq(values.iter().rev()) => vec![Pattern::Palindrome],
+        Rule::Palindrome => Vec::new(),
+        // an ID repeating a block exactly is not one with a digit changed
+        Rule::NearRepeat if divisors(length).any(periodic) => Vec::new(),
+        Rule::NearRepeat => divisors(length).filter_map(|block| near_repeat(&values, block)).collect(),
+    }
+}
+
+// the repeated IDs are a block of digits repeated: with R = (base^(block * repeats) - 1) / (base^block - 1), a 1
+// followed by another 1 every `block` digits, they are B * R for the blocks B of `block` digits, so they are generated
+// without looking at the IDs in between and their sum in a range is R times an arithmetic series
+
+// R for a block repeated to `length` digits, which fits as long as the IDs of `length` digits do
+fn repunit(block: u32, length: u32, base: Base) -> Id {
+    (0..length / block).fold(0, |repunit, _| repunit * base.pow(block) + 1)
+}
+
+// the lengths of the blocks repeated min to max times to `length` digits and of all the blocks they repeat in turn:
+// every repeated ID of `length` digits repeats exactly one of these that is not a repetition itself
+fn periods(length: u32, min: u32, max: u32) -> Vec<u32> {
+    let blocks = divisors(length).filter(|block| (min..=max).contains(&(length / block))).collect::<Vec<u32>>();
+    divisors(length).filter(|period| blocks.iter().any(|block| block.is_multiple_of(*period))).collect()
+}
+
+// the blocks of `block` digits whose repetitions to `length` digits are in lo..=hi, and R
+fn blocks(lo: Id, hi: Id, length: u32, block: u32, base: Base) -> (RangeInclusive<Id>, Id) {
+    let repunit = repunit(block, length, base);
+    let (lo, hi) = base.within(lo, hi, length);
+    let first = (lo / repunit + Id::from(!lo.is_multiple_of(repunit))).max(base.pow(block - 1));
+    let last = (hi / repunit).min(base.pow(block) - 1);
+    (first..=last, repunit)
+}
+
+fn overflow() -> AocError {
+    AocError::overflow("the sum of the invalid IDs")
+}
+
+// how many repetitions of a block of `block` digits to `length` digits are in lo..=hi, including those of shorter
+// blocks, and their sum
+fn block_tally(lo: Id, hi: Id, length: u32, block: u32, base: Base) -> Result<(Id, Id), AocError> {
+    let (blocks, repunit) = blocks(lo, hi, length, block, base);
+    if blocks.is_empty() {
+        return Ok((0, 0));
+    }
+    let (first, last) = (*blocks.start(), *blocks.end());
+    // one of the two is even, and with blocks of at most half the digits of an ID neither is beyond 2^65
+    let (ends, count) = (first + last, last - first + 1);
+    let series = if count.is_multiple_of(2) { ends * (count / 2) } else { ends / 2 * count };
+    Ok((count, repunit.checked_mul(series).ok_or_else(overflow)?))
+}
+
+// how many repeated IDs are in lo..=hi, and their sum, as many steps as there are block lengths
+fn repeated_tally(lo: Id, hi: Id, min: u32, max: u32, base: Base) -> Result<(Id, Id), AocError> {
+    let (mut count, mut sum): (Id, Id) = (0, 0);
+    if lo > hi {
+        return Ok((count, sum));
+    }
+    for length in base.digits(lo)..=base.digits(hi) {
+        // a block repeating a shorter one gives IDs already counted for that one (12121212 is 1212 twice and 12
+        // four times), so the tallies of the shorter blocks dividing it are taken off, leaving those of exactly its length
+        let periods = periods(length, min, max);
+        let mut exact: Vec<(Id, Id)> = Vec::with_capacity(periods.len());
+        for (index, block) in periods.iter().enumerate() {
+            let (mut count_of_block, mut sum_of_block) = block_tally(lo, hi, length, *block, base)?;
+            for (shorter, (shorter_count, shorter_sum)) in periods[..index].iter().zip(&exact) {
+                if block.is_multiple_of(*shorter) {
+                    count_of_block -= shorter_count;
+                    sum_of_block -= shorter_sum;
+                }
+            }
+            exact.push((count_of_block, sum_of_block));
+            // the count is never more than the sum
+            count += count_of_block;
+            sum = sum.checked_add(sum_of_block).ok_or_else(overflow)?;
+        }
+    }
+    Ok((count, sum))
+}
+
+// how many invalid IDs are in lo..=hi, and their sum: in closed form for the repetitions, one ID at a time otherwise
+pub fn tally(lo: Id, hi: Id, rule: Rule, base: Base) -> Result<(Id, Id), AocError> {
+    match rule {
+        Rule::Repeated { min, max } => repeated_tally(lo, hi, min, max, base),
+        _ => InvalidIds::new(lo, hi, rule, base)
+            .try_fold((0, 0), |(count, sum): (Id, Id), found| Ok((count + 1, sum.checked_add(found.id).ok_or_else(overflow)?))),
+    }
+}
+
+pub fn sum_invalid(lo: Id, hi: Id, rule: Rule, base: Base) -> Result<Id, AocError> {
+    Ok(tally(lo, hi, rule, base)?.1)
+}
+
+// the block is a repetition of a shorter one itself
+fn is_repeated(block: Id, block_length: u32, base: Base) -> bool {
+    divisors(block_length).any(|shorter| block.is_multiple_of(repunit(shorter, block_length, base)))
+}
+
+// the palindrome of `length` digits whose first half (with the middle digit) is `half`, None when it does not fit
+fn mirror(half: Id, length: u32, base: Base) -> Option<Id> {
+    let radix = Id::from(base.radix());
+    let mut rest = if length.is_multiple_of(2) { half } else { half / radix };
+    let mut palindrome = half;
+    for _ in 0..length / 2 {
+        palindrome = palindrome.checked_mul(radix)?.checked_add(rest % radix)?;
+        rest /= radix;
+    }
+    Some(palindrome)
+}
+
+// the first halves of the palindromes of `length` digits in lo..=hi, the larger the half the larger the palindrome
+fn halves(lo: Id, hi: Id, length: u32, base: Base) -> RangeInclusive<Id> {
+    let (lo, hi) = base.within(lo, hi, length);
+    if lo > hi {
+        return RangeInclusive::new(1, 0);
+    }
+    let shift = base.pow(length / 2);
+    let (mut first, mut last) = (lo / shift, hi / shift);
+    if mirror(first, length, base).is_some_and(|palindrome| palindrome < lo) {
+        first += 1;
+    }
+    if mirror(last, length, base).is_none_or(|palindrome| palindrome > hi) {
+        last -= 1;
+    }
+    first..=last
+}
+
+// the near-repeats of `length` digits are looked for under their leading digits, as many as the longest block has, so
+// that all of them have the blocks they may repeat in common
+fn lead_length(length: u32) -> u32 {
+    divisors(length).max().unwrap_or(0)
+}
+
+fn leads(lo: Id, hi: Id, length: u32, base: Base) -> RangeInclusive<Id> {
+    let (lo, hi) = base.within(lo, hi, length);
+    if lo > hi || length == 1 {
+        return RangeInclusive::new(1, 0);
+    }
+    let shift = base.pow(length - lead_length(length));
+    lo / shift..=hi / shift
+}
+
+// the near-repeats in lo..=hi of `length` digits starting with `lead`: for each block, the first block of the lead
+// repeated with a digit of one of the other blocks changed, or a digit of the first block changed and the rest repeating
+// the block it was changed from
+fn near_repeats_under(lead: Id, lo: Id, hi: Id, length: u32, base: Base) -> BTreeSet<Id> {
+    let lead_values = base.digit_values(lead);
+    let mut found = BTreeSet::new();
+    for block in divisors(length) {
+        let (width, repeats) = (block as usize, (length / block) as usize);
+        let first = &lead_values[..width];
+        let mut candidates = Vec::new();
+        for position in width..length as usize {
+            for value in (0..base.radix()).filter(|value| *value != first[position % width]) {
+                let mut values = first.repeat(repeats);
+                values[position] = value;
+                candidates.push(values);
+            }
+        }
+        for position in (0..width).filter(|_| repeats >= 3) {
+            for value in (0..base.radix()).filter(|value| *value != first[position]) {
+                let mut repeated = first.to_vec();
+                repeated[position] = value;
+                candidates.push([first, &repeated.repeat(repeats - 1)].concat());
+            }
+        }
+        for values in candidates.into_iter().filter(|values| values.starts_with(&lead_values)) {
+            let Some(id) = base.number(&values) else {
+                continue;
+            };
+            if (lo..=hi).contains(&id) && !detect(id, Rule::NearRepeat, base).is_empty() {
+                found.insert(id);
+            }
+        }
+    }
+    found
+}
+
+// an invalid ID with every pattern it matches, shortest block first, and the base of their digits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub id: Id,
+    pub base: Base,
+    pub patterns: Vec<Pattern>,
+}
+
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let patterns = self.patterns.iter().map(Pattern::to_string).collect::<Vec<String>>();
+        if self.base != Base::DECIMAL {
+            write!(f, "{} = {} in base {} ({})", self.id, self.base.format(self.id), self.base, patterns.join(", "))
+        } else {
+            write!(f, "{} ({})", self.id, patterns.join(", "))
+        }
+    }
+}
+
+// the invalid IDs of one number of digits still to go through
+enum Source {
+    // for each block length not repeating a shorter one, the blocks still to repeat and R, merged as they go
+    Repeated(Vec<(u32, RangeInclusive<Id>, Id)>),
+    // the first halves still to mirror
+    Palindromes(RangeInclusive<Id>),
+    // the leads still to look under, and the IDs found under the last one
+    NearRepeats { leads: RangeInclusive<Id>, found: btree_set::IntoIter<Id> },
+}
 
-                // create an array of the pieces
-                let pieces = num_stringized.chars().collect::<Vec<char>>().chunks(divisor).map(|chunk| chunk.iter().collect::<String>()).collect::<Vec<String>>().clone();
-                // check if the first half is equal to the second half
-                if compare_texts_in_array(&pieces) {
-                    result += num;
-                    continue 'the_outer_loop;
+// the invalid IDs of a range in ascending order, one number of digits after the other, each with its patterns
+pub struct InvalidIds {
+    lo: Id,
+    hi: Id,
+    rule: Rule,
+    base: Base,
+    length: u32,
+    last_length: u32,
+    source: Source,
+}
+
+impl InvalidIds {
+    pub fn new(lo: Id, hi: Id, rule: Rule, base: Base) -> Self {
+        // an empty range has no lengths to go through
+        let (length, last_length) = if lo > hi { (1, 0) } else { (base.digits(lo), base.digits(hi)) };
+        let mut ids = Self { lo, hi, rule, base, length, last_length, source: Source::Repeated(Vec::new()) };
+        ids.start_length();
+        ids
+    }
+
+    fn start_length(&mut self) {
+        if self.length > self.last_length {
+            return;
+        }
+        let (lo, hi, length, base) = (self.lo, self.hi, self.length, self.base);
+        self.source = match self.rule {
+            Rule::Repeated { min, max } => Source::Repeated(
+                periods(length, min, max)
+                    .into_iter()
+                    .map(|block| {
+                        let (blocks, repunit) = blocks(lo, hi, length, block, base);
+                        (block, blocks, repunit)
+                    })
+                    .collect(),
+            ),
+            Rule::Palindrome => Source::Palindromes(halves(lo, hi, length, base)),
+            Rule::NearRepeat => Source::NearRepeats { leads: leads(lo, hi, length, base), found: BTreeSet::new().into_iter() },
+        };
+    }
+
+    // the next ID of the current number of digits
+    fn next_id(&mut self) -> Option<Id> {
+        let (lo, hi, length, base) = (self.lo, self.hi, self.length, self.base);
+        match &mut self.source {
+            Source::Repeated(streams) => loop {
+                let (block, blocks, repunit) = streams
+                    .iter_mut()
+                    .filter(|(_, blocks, _)| !blocks.is_empty())
+                    .min_by_key(|(_, blocks, repunit)| *blocks.start() * *repunit)?;
+                let num = blocks.next()?;
+                if !is_repeated(num, *block, base) {
+                    return Some(num * *repunit);
+                }
+            },
+            Source::Palindromes(halves) => halves.next().and_then(|half| mirror(half, length, base)),
+            Source::NearRepeats { leads, found } => loop {
+                if let Some(id) = found.next() {
+                    return Some(id);
                 }
+                *found = near_repeats_under(leads.next()?, lo, hi, length, base).into_iter();
+            },
+        }
+    }
+}
+
+impl Iterator for InvalidIds {
+    type Item = Match;
 
-                divisor += 1;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.length <= self.last_length {
+            match self.next_id() {
+                Some(id) => return Some(Match { id, base: self.base, patterns: detect(id, self.rule, self.base) }),
+                None => {
+                    self.length += 1;
+                    self.start_length();
+                }
             }
         }
+        None
+    }
+}
+
+pub fn invalid_ids(lo: Id, hi: Id, rule: Rule, base: Base) -> Vec<Id> {
+    InvalidIds::new(lo, hi, rule, base).map(|found| found.id).collect()
+}
+
+// what a range adds to the answer: all its invalid IDs, unless there are more than a limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeReport {
+    pub lo: Id,
+    pub hi: Id,
+    pub count: Id,
+    pub sum: Id,
+    pub matches: Vec<Match>,
+}
+
+impl fmt::Display for RangeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}: {} invalid IDs, sum {}", self.lo, self.hi, self.count, self.sum)?;
+        for found in &self.matches {
+            write!(f, "\n    {}", found)?;
+        }
+        if (self.matches.len() as Id) < self.count {
+            write!(f, "\n    ... {} more", self.count - self.matches.len() as Id)?;
+        }
+        Ok(())
     }
-    return result
 }
 
+pub fn day02_breakdown(input: &str, rule: Rule, base: Base, limit: Option<usize>) -> Result<Vec<RangeReport>, AocError> {
+    let mut reports = Vec::new();
+    for pair in parse_pairs(input)? {
+        let (lo, hi) = (pair[0], pair[1]);
+        let (count, sum) = tally(lo, hi, rule, base)?;
+        let matches = InvalidIds::new(lo, hi, rule, base).take(limit.unwrap_or(usize::MAX)).collect();
+        reports.push(RangeReport { lo, hi, count, sum, matches });
+    }
+    Ok(reports)
+}
+
+fn sum_pairs(input: &str, rule: Rule, base: Base) -> Result<Id, AocError> {
+    let mut result: Id = 0;
+    // iterate all the pairs
+    for pair in parse_pairs(input)? {
+        result = result.checked_add(sum_invalid(pair[0], pair[1], rule, base)?).ok_or_else(overflow)?;
+    }
+    Ok(result)
+}
+
+// a rule given as a parameter replaces the one of the part, the base is decimal unless given
+fn rule_of(params: &Params, default: Rule) -> Result<(Rule, Base), AocError> {
+    Ok((params.parsed::<Rule>("rule")?.unwrap_or(default), params.parsed::<Base>("base")?.unwrap_or(Base::DECIMAL)))
+}
+
+pub fn day02_part1_from_str(input: &str) -> Result<Id, AocError> {
+    sum_pairs(input, Rule::PART1, Base::DECIMAL)
+}
+
+pub fn day02_part1_with_params(input: &str, params: &Params) -> Result<Id, AocError> {
+    let (rule, base) = rule_of(params, Rule::PART1)?;
+    sum_pairs(input, rule, base)
+}
+
+pub fn day02_part1_from_reader<R: BufRead>(reader: R) -> Result<Id, AocError> {
+    day02_part1_from_str(&read_input(reader)?)
+}
+
+pub fn day02_part1(filename: &str) -> Result<Id, AocError> {
+    day02_part1_from_reader(open_input(filename)?)
+}
+
+
+pub fn day02_part2_from_str(input: &str) -> Result<Id, AocError> {
+    sum_pairs(input, Rule::PART2, Base::DECIMAL)
+}
+
+pub fn day02_part2_with_params(input: &str, params: &Params) -> Result<Id, AocError> {
+    let (rule, base) = rule_of(params, Rule::PART2)?;
+    sum_pairs(input, rule, base)
+}
+
+pub fn day02_part2_from_reader<R: BufRead>(reader: R) -> Result<Id, AocError> {
+    day02_part2_from_str(&read_input(reader)?)
+}
+
+pub fn day02_part2(filename: &str) -> Result<Id, AocError> {
+    day02_part2_from_reader(open_input(filename)?)
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Answer = Id;
+
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Gift Shop"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        parse_pairs(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        day02_part1_from_str(input)
+    }
+
+    fn part2(&self, input: &str) -> Option<Result<Self::Answer, AocError>> {
+        Some(day02_part2_from_str(input))
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        &["rule", "base"]
+    }
+
+    fn part1_with(&self, input: &str, params: &Params) -> Result<Self::Answer, AocError> {
+        day02_part1_with_params(input, params)
+    }
+
+    fn part2_with(&self, input: &str, params: &Params) -> Option<Result<Self::Answer, AocError>> {
+        Some(day02_part2_with_params(input, params))
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers::expected;
+    use crate::property::{seed, Rng};
+    use crate::Part;
+
+    const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+
+    #[test]
+    fn day02_from_str() {
+        assert_eq!(day02_part1_from_str(EXAMPLE).unwrap(), 1227775554);
+        assert_eq!(day02_part2_from_str(EXAMPLE).unwrap(), 4174379265);
+    }
+
+    #[test]
+    fn day02_from_reader() {
+        assert_eq!(day02_part1_from_reader(EXAMPLE.as_bytes()).unwrap(), 1227775554);
+    }
 
     #[test]
     fn day02_part1_a() {
-        let result = day02_part1("input/day02a.txt");
-        assert_eq!(result, 1227775554);
+        let result = day02_part1("input/day02a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(2, Part::One, "day02a.txt"));
     }
 
     #[test]
     fn day02_part1_b() {
-        let result = day02_part1("input/day02b.txt");
-        assert_eq!(result, 21898734247);
+        let result = day02_part1("input/day02b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(2, Part::One, "day02b.txt"));
     }
 
     #[test]
     fn day02_part2_a() {
-        let result = day02_part2("input/day02a.txt");
-        assert_eq!(result, 4174379265);
+        let result = day02_part2("input/day02a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(2, Part::Two, "day02a.txt"));
     }
 
     #[test]
     fn day02_part2_b() {
-        let result = day02_part2("input/day02b.txt");
-        assert_eq!(result, 28915664389);
+        let result = day02_part2("input/day02b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(2, Part::Two, "day02b.txt"));
+    }
+
+    #[test]
+    fn day02_parse_reports_location() {
+        let error = parse_pairs("11-22,95-1x5").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 10: invalid number: \"1x5\"");
+        let error = parse_pairs("11-22,95").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 7: expected a range, found \"95\"");
+        let error = parse_pairs("1-3,5-3").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 5: range starts after it ends: 5-3");
+    }
+
+    // the IDs one by one, as they were checked before they were generated
+    fn is_invalid(num: Id, min: usize, max: usize) -> bool {
+        let text = num.to_string();
+        (min..=max.min(text.len())).filter(|repeats| text.len().is_multiple_of(*repeats)).any(|repeats| {
+            let block = &text[..text.len() / repeats];
+            text == block.repeat(repeats)
+        })
+    }
+
+    #[test]
+    fn day02_generated_ids_match_the_checked_ones() {
+        let mut rng = Rng::new(seed(21));
+        for _ in 0..300 {
+            let lo = rng.range(0, 2_000_000) as Id;
+            let hi = lo + rng.range(0, 5_000) as Id;
+            let min = rng.range(2, 4) as u32;
+            let max = min + rng.range(0, 3) as u32;
+            for rule in [Rule::PART1, Rule::PART2, Rule::Repeated { min, max }] {
+                let Rule::Repeated { min, max } = rule else { unreachable!() };
+                let checked = (lo..=hi).filter(|num| is_invalid(*num, min as usize, max as usize)).collect::<Vec<Id>>();
+                assert_eq!(invalid_ids(lo, hi, rule, Base::DECIMAL), checked, "{}-{} {}", lo, hi, rule);
+                let expected = (checked.len() as Id, checked.iter().sum::<Id>());
+                assert_eq!(tally(lo, hi, rule, Base::DECIMAL).unwrap(), expected, "{}-{} {}", lo, hi, rule);
+            }
+        }
+    }
+
+    #[test]
+    fn day02_generated_ids_match_the_detected_ones() {
+        let mut rng = Rng::new(seed(24));
+        for _ in 0..150 {
+            let lo = Id::pow(10, rng.range(0, 8) as u32) + rng.range(0, 1_000_000) as Id;
+            let hi = lo + rng.range(0, 3_000) as Id;
+            for rule in [Rule::Palindrome, Rule::NearRepeat] {
+                let detected = (lo..=hi).filter(|num| !detect(*num, rule, Base::DECIMAL).is_empty()).collect::<Vec<Id>>();
+                assert_eq!(invalid_ids(lo, hi, rule, Base::DECIMAL), detected, "{}-{} {}", lo, hi, rule);
+            }
+        }
+        // every near-repeat of up to four digits
+        let detected = (1..10_000).filter(|num| !detect(*num, Rule::NearRepeat, Base::DECIMAL).is_empty()).collect::<Vec<Id>>();
+        assert_eq!(invalid_ids(1, 9_999, Rule::NearRepeat, Base::DECIMAL), detected);
+    }
+
+    #[test]
+    fn day02_generated_ids_match_the_detected_ones_in_any_base() {
+        let mut rng = Rng::new(seed(25));
+        for _ in 0..200 {
+            let base = Base::new(rng.range(2, 36) as u32).unwrap();
+            let lo = rng.range(0, 300_000) as Id;
+            let hi = lo + rng.range(0, 2_000) as Id;
+            let (min, max) = (rng.range(2, 3) as u32, rng.range(3, 5) as u32);
+            for rule in [Rule::PART1, Rule::PART2, Rule::Repeated { min, max }, Rule::Palindrome, Rule::NearRepeat] {
+                let detected = (lo..=hi).filter(|num| !detect(*num, rule, base).is_empty()).collect::<Vec<Id>>();
+                assert_eq!(invalid_ids(lo, hi, rule, base), detected, "{}-{} {} base {}", lo, hi, rule, base);
+                let expected = (detected.len() as Id, detected.iter().sum::<Id>());
+                assert_eq!(tally(lo, hi, rule, base).unwrap(), expected, "{}-{} {} base {}", lo, hi, rule, base);
+            }
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn day02_bases() {
+        let base = |radix: u32| Base::new(radix).unwrap();
+        // 0xabab, 0o7777 and 0b101101
+        assert_eq!(detect(0xabab, Rule::PART1, base(16)), vec![Pattern::Repeated { block: 2, repeats: 2 }]);
+        assert_eq!(detect(0o7777, Rule::PART2, base(8)).len(), 2);
+        assert_eq!(detect(0b101101, Rule::PART1, base(2)), vec![Pattern::Repeated { block: 3, repeats: 2 }]);
+        // which is 45, no repetition in decimal
+        assert!(detect(45, Rule::PART1, Base::DECIMAL).is_empty());
+        // 3, 7, 10 and 15 are 11, 111, 1010 and 1111 in binary, only 7 is not a repetition of two blocks
+        assert_eq!(invalid_ids(1, 15, Rule::PART2, base(2)), vec![3, 7, 10, 15]);
+        let found = InvalidIds::new(10, 10, Rule::PART1, base(2)).next().unwrap();
+        assert_eq!(found.to_string(), "10 = 1010 in base 2 (2x2)");
+        assert_eq!(base(36).format(36 * 36 - 1), "zz");
+        // every ID up to the largest, all 128 ones in binary
+        assert_eq!(invalid_ids(Id::MAX - 1, Id::MAX, Rule::PART2, base(2)), vec![Id::MAX]);
+        assert_eq!(invalid_ids(Id::MAX - 1, Id::MAX, Rule::Palindrome, base(2)), vec![Id::MAX]);
+        // the parameters of the runner
+        let params = "rule=x2,base=2".parse::<Params>().unwrap();
+        assert_eq!(day02_part1_with_params("1-15", &params).unwrap(), 3 + 10 + 15);
+        for radix in ["1", "37", "ten"] {
+            let params = format!("base={}", radix).parse::<Params>().unwrap();
+            assert_eq!(day02_part2_with_params("1-15", &params).err().unwrap().to_string(), format!("invalid value for base: {}", radix));
+        }
+        assert_eq!(Base::new(0).err().unwrap().to_string(), "base must be from 2 to 36, found 0");
+    }
+
+    #[test]
+    fn day02_detect_patterns() {
+        let patterns = |id: Id, rule: &str| detect(id, rule.parse().unwrap(), Base::DECIMAL).iter().map(Pattern::to_string).collect::<Vec<String>>();
+        assert_eq!(patterns(121212, "x2+"), vec!["2x3"]);
+        assert_eq!(patterns(222222, "x3"), vec!["2x3"]);
+        assert_eq!(patterns(222222, "x2-3"), vec!["2x3", "3x2"]);
+        assert!(patterns(222222, "x4-5").is_empty());
+        assert_eq!((patterns(12321, "palindrome"), patterns(7, "palindrome")), (vec!["palindrome".to_string()], vec![]));
+        assert_eq!(patterns(1121, "near-repeat"), vec!["1x4 but digit 3", "2x2 but digit 3"]);
+        assert_eq!(patterns(1213, "near-repeat"), vec!["2x2 but digit 4"]);
+        assert_eq!(patterns(212121, "near-repeat"), Vec::<String>::new());
+        assert_eq!(patterns(313131, "near-repeat"), Vec::<String>::new());
+        assert_eq!(patterns(323131, "near-repeat"), vec!["2x3 but digit 2"]);
+        // 1000 is not 0000 with a digit changed, but it is 1010
+        assert_eq!(patterns(1000, "near-repeat"), vec!["2x2 but digit 3"]);
+    }
+
+    #[test]
+    fn day02_rules() {
+        for (text, rule) in [("x2", Rule::PART1), ("x2+", Rule::PART2), ("x3-5", Rule::Repeated { min: 3, max: 5 }), ("palindrome", Rule::Palindrome), ("near-repeat", Rule::NearRepeat)] {
+            assert_eq!(text.parse::<Rule>().unwrap(), rule);
+            assert_eq!(rule.to_string(), text);
+        }
+        for text in ["x1", "x1+", "x4-3", "2", "x", "twice"] {
+            assert_eq!(text.parse::<Rule>().err().unwrap(), format!("invalid rule: {}", text));
+        }
+        // a rule given to either part replaces its own
+        let params = "rule=x2+".parse::<Params>().unwrap();
+        assert_eq!(day02_part1_with_params(EXAMPLE, &params).unwrap(), 4174379265);
+        let params = "rule=palindrome".parse::<Params>().unwrap();
+        assert_eq!(day02_part2_with_params("95-115", &params).unwrap(), 99 + 101 + 111);
+        let params = "rule=x1".parse::<Params>().unwrap();
+        assert_eq!(day02_part1_with_params(EXAMPLE, &params).err().unwrap().to_string(), "invalid value for rule: x1");
+    }
+
+    #[test]
+    fn day02_wide_ranges() {
+        // 9 + 90 + ... + 90000 doubled blocks of up to ten digits
+        let ids = invalid_ids(1, 9_999_999_999, Rule::PART1, Base::DECIMAL);
+        assert_eq!(ids.len(), 99_999);
+        assert_eq!(sum_invalid(1, 9_999_999_999, Rule::PART1, Base::DECIMAL).unwrap(), ids.iter().sum::<Id>());
+        let ids = invalid_ids(1, 9_999_999_999, Rule::PART2, Base::DECIMAL);
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(sum_invalid(1, 9_999_999_999, Rule::PART2, Base::DECIMAL).unwrap(), ids.iter().sum::<Id>());
+        // 19 digits are only ever a single digit repeated
+        let ids = invalid_ids(1_111_111_111_111_111_110, 9_999_999_999_999_999_999, Rule::PART2, Base::DECIMAL);
+        assert_eq!(ids, (1..=9).map(|digit| digit * 1_111_111_111_111_111_111).collect::<Vec<Id>>());
+        assert_eq!(sum_invalid(10, 5, Rule::PART1, Base::DECIMAL).unwrap(), 0);
+        // the sum of (10^b + 1) * (10^(b - 1) + ... + 10^b - 1) for b up to 9 is beyond an i64
+        assert_eq!(sum_invalid(1, 999_999_999_999_999_999, Rule::PART1, Base::DECIMAL).unwrap(), 495_495_495_540_950_040_450_040_950);
+    }
+
+    #[test]
+    fn day02_ids_up_to_u128() {
+        // the largest ID has 39 digits and starts with 3
+        let ones = "1".repeat(39).parse::<Id>().unwrap();
+        let input = format!("{}-{},{}-{}", ones, ones, ones * 2 - 5, ones * 2 + 5);
+        assert_eq!(day02_part2_from_str(&input).unwrap(), ones * 3);
+        assert_eq!(invalid_ids(ones * 3 - 10, ones * 3 + 10, Rule::PART2, Base::DECIMAL), vec![ones * 3]);
+        // the sum of the two does not fit
+        let input = format!("{}-{},{}-{}", ones, ones, ones * 3, ones * 3);
+        let error = day02_part2_from_str(&input).err().unwrap();
+        assert_eq!(error.to_string(), "arithmetic overflow: the sum of the invalid IDs");
+        let ids = InvalidIds::new(ones * 3, Id::MAX, Rule::PART2, Base::DECIMAL).take(3).map(|found| found.id.to_string()).collect::<Vec<String>>();
+        assert_eq!(ids, vec!["3".repeat(39), "3333333333334".repeat(3), "3333333333335".repeat(3)]);
+        let error = parse_pairs(&format!("1-{}0", Id::MAX)).err().unwrap();
+        assert_eq!(error.to_string(), format!("line 1, column 3: number out of range: \"{}0\"", Id::MAX));
+    }
+
+    #[test]
+    fn day02_breakdown_of_the_example() {
+        let reports = day02_breakdown(EXAMPLE, Rule::PART2, Base::DECIMAL, None).unwrap();
+        assert_eq!(reports.len(), 11);
+        assert_eq!(reports.iter().map(|report| report.sum).sum::<Id>(), 4174379265);
+        assert_eq!(reports[1].to_string(), "95-115: 2 invalid IDs, sum 210\n    99 (1x2)\n    111 (1x3)");
+        assert_eq!(reports[4].matches, vec![Match { id: 222222, base: Base::DECIMAL, patterns: vec![Pattern::Repeated { block: 1, repeats: 6 }, Pattern::Repeated { block: 2, repeats: 3 }, Pattern::Repeated { block: 3, repeats: 2 }] }]);
+        assert_eq!(reports[10].matches[0].to_string(), "2121212121 (2x5)");
+        let reports = day02_breakdown(EXAMPLE, Rule::PART1, Base::DECIMAL, None).unwrap();
+        assert_eq!(reports[1].to_string(), "95-115: 1 invalid IDs, sum 99\n    99 (1x2)");
+        assert!(reports[10].matches.is_empty());
+        // the count and the sum are of the whole range, the IDs up to the limit
+        let reports = day02_breakdown("1-9999", Rule::PART1, Base::DECIMAL, Some(2)).unwrap();
+        assert_eq!(reports[0].to_string(), "1-9999: 99 invalid IDs, sum 495900\n    11 (1x2)\n    22 (1x2)\n    ... 97 more");
+    }
+}
@@ -1,13 +1,25 @@
-use std::fs;
+use std::io::BufRead;
 
-pub fn day03_part1(filename: &str) -> u64 {
+use crate::{open_input, read_input, AocError, Solution};
+
+// every line has to be a bank of at least `min_len` digits
+fn validate_line(line: &str, line_idx: usize, min_len: usize) -> Result<(), AocError> {
+    if let Some((column_idx, char)) = line.char_indices().find(|(_, char)| !char.is_ascii_digit()) {
+        return Err(AocError::malformed(line_idx + 1, column_idx + 1, format!("expected a digit, found {:?}", char)));
+    }
+    if line.len() < min_len {
+        return Err(AocError::malformed(line_idx + 1, line.len() + 1, format!("expected at least {} digits", min_len)));
+    }
+    Ok(())
+}
+
+pub fn day03_part1_from_str(input: &str) -> Result<u64, AocError> {
     let mut result = 0u64;
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
     // split the input into lines
     let lines = input.lines();
     // iterate over the lines
-    for line in lines {
+    for (line_idx, line) in lines.enumerate() {
+        validate_line(line, line_idx, 2)?;
         // find the highest digit in the line, excluding the final character (if it appears more than once, pick the first one)
         let highest_digit = line[..line.len() - 1].chars().max().unwrap();
         // select the range between the character after the highest_digit and the end of the line
@@ -18,17 +30,24 @@ pub fn day03_part1(filename: &str) -> u64 {
         let to_be_added = highest_digit.to_digit(10).unwrap() as u64 * 10 + highest_digit_in_range.to_digit(10).unwrap() as u64;
         result += to_be_added;
     }
-    return result;
+    Ok(result)
+}
+
+pub fn day03_part1_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    day03_part1_from_str(&read_input(reader)?)
+}
+
+pub fn day03_part1(filename: &str) -> Result<u64, AocError> {
+    day03_part1_from_reader(open_input(filename)?)
 }
 
-pub fn day03_part2(filename: &str) -> u64 {
+pub fn day03_part2_from_str(input: &str) -> Result<u64, AocError> {
     let mut result = 0;
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
     // split the input into lines
     let lines = input.lines();
     // iterate over the lines
-    for line in lines {
+    for (line_idx, line) in lines.enumerate() {
+        validate_line(line, line_idx, 12)?;
         let mut digits_left = 12;
         let mut to_be_added_total: u64 = 0;
         let mut searched_through = line.to_string();
@@ -46,34 +65,91 @@ pub fn day03_part2(filename: &str) -> u64 {
         }
         result += to_be_added_total;
     }
-    return result;
+    Ok(result)
+}
+
+pub fn day03_part2_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    day03_part2_from_str(&read_input(reader)?)
+}
+
+pub fn day03_part2(filename: &str) -> Result<u64, AocError> {
+    day03_part2_from_reader(open_input(filename)?)
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Answer = u64;
+
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Lobby"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        input.lines().enumerate().try_for_each(|(line_idx, line)| validate_line(line, line_idx, 2))
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        day03_part1_from_str(input)
+    }
+
+    fn part2(&self, input: &str) -> Option<Result<Self::Answer, AocError>> {
+        Some(day03_part2_from_str(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers::expected;
+    use crate::Part;
+
+    const EXAMPLE: &str = "987654321111111\n811111111111119\n234234234234278\n818181911112111\n";
+
+    #[test]
+    fn day03_from_str() {
+        assert_eq!(day03_part1_from_str(EXAMPLE).unwrap(), 357);
+        assert_eq!(day03_part2_from_str(EXAMPLE).unwrap(), 3121910778619);
+    }
+
+    #[test]
+    fn day03_from_reader() {
+        assert_eq!(day03_part1_from_reader(EXAMPLE.as_bytes()).unwrap(), 357);
+    }
 
     #[test]
     fn day03_part1_a() {
-        let result = day03_part1("input/day03a.txt");
-        assert_eq!(result, 357);
+        let result = day03_part1("input/day03a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(3, Part::One, "day03a.txt"));
     }
 
     #[test]
     fn day03_part1_b() {
-        let result = day03_part1("input/day03b.txt");
-        assert_eq!(result, 17263);
+        let result = day03_part1("input/day03b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(3, Part::One, "day03b.txt"));
     }
 
     #[test]
     fn day03_part2_a() {
-        let result = day03_part2("input/day03a.txt");
-        assert_eq!(result, 3121910778619);
+        let result = day03_part2("input/day03a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(3, Part::Two, "day03a.txt"));
     }
 
     #[test]
     fn day03_part2_b() {
-        let result = day03_part2("input/day03b.txt");
-        assert_eq!(result, 170731717900423);
+        let result = day03_part2("input/day03b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(3, Part::Two, "day03b.txt"));
+    }
+
+    #[test]
+    fn day03_validate_reports_location() {
+        let error = validate_line("98765x321", 4, 2).err().unwrap();
+        assert_eq!(error.to_string(), "line 5, column 6: expected a digit, found 'x'");
+        let error = validate_line("987", 0, 12).err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 4: expected at least 12 digits");
     }
 }
\ No newline at end of file
@@ -1,6 +1,9 @@
-use std::fs;
+use std::io::BufRead;
 use std::collections::HashMap;
 
+use crate::trace::{self, Verbosity};
+use crate::{open_input, read_input, AocError, Solution};
+
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 struct Position {
     x: u8,
@@ -78,50 +81,74 @@ impl Floor {
         }
     }
 
-    fn _print(&self, size: u8) {
+    fn render(&self, size: u8) -> String {
+        let mut text = String::new();
         for y in 0..size {
             for x in 0..size {
                 let tile = self.floor_map.get(&Position::new(x, y)).unwrap();
                 if tile.is_filled {
-                    print!("@");
+                    text.push('@');
                 } else {
-                    print!(".");
+                    text.push('.');
                 }
             }
-            println!();
+            text.push('\n');
         }
+        text
     }
 
-    fn _print_neighbors(&self, size: u8) {
+    fn render_neighbors(&self, size: u8) -> String {
+        let mut text = String::new();
         for y in 0..size {
             for x in 0..size {
                 let tile = self.floor_map.get(&Position::new(x, y)).unwrap();
-                print!("{}", tile.neighbors);
+                text.push_str(&tile.neighbors.to_string());
             }
-            println!();
+            text.push('\n');
         }
+        text
     }
 }
 
-pub fn day04_part1(filename: &str, size: u8) -> u64 {
-    let mut result = 0;
-    // create the map, indexed with u8 pair, the value is a pair: boolean and u16
-    let mut floor = Floor::new(size);
-    // read the file, named 'filename'
-    let input = fs::read_to_string(filename).unwrap();
+// the floor is a square, so its size is the number of lines in the input
+pub fn floor_size(input: &str) -> Result<u8, AocError> {
+    match input.lines().count() {
+        0 => Err(AocError::domain("empty grid")),
+        size if size > u8::MAX as usize => Err(AocError::domain(format!("grid too large: {} lines", size))),
+        size => Ok(size as u8),
+    }
+}
+
+fn fill_floor(floor: &mut Floor, input: &str, size: u8) -> Result<(), AocError> {
+    if input.lines().next().is_none() {
+        return Err(AocError::domain("empty grid"));
+    }
     // for every line ...
     for (line_idx, line) in input.lines().enumerate() {
+        if line_idx >= size as usize {
+            return Err(AocError::malformed(line_idx + 1, 1, format!("grid has more than {} lines", size)));
+        }
         // ... and the intersection with every column ...
         for (column_idx, char) in line.chars().enumerate() {
-            // ... mark the boolean as full/empty
-            if char == '.' {
-                floor.floor_map.get_mut(&Position::new(column_idx as u8, line_idx as u8)).unwrap().is_filled = false;
-            } else {
-                floor.floor_map.get_mut(&Position::new(column_idx as u8, line_idx as u8)).unwrap().is_filled = true;
+            if column_idx >= size as usize {
+                return Err(AocError::malformed(line_idx + 1, column_idx + 1, format!("line is longer than {} tiles", size)));
             }
+            if char != '.' && char != '@' {
+                return Err(AocError::malformed(line_idx + 1, column_idx + 1, format!("unexpected tile {:?}", char)));
+            }
+            // ... mark the boolean as full/empty
+            floor.floor_map.get_mut(&Position::new(column_idx as u8, line_idx as u8)).unwrap().is_filled = char != '.';
         }
     }
-    //floor.print(size);
+    Ok(())
+}
+
+pub fn day04_part1_from_str(input: &str, size: u8) -> Result<u64, AocError> {
+    let mut result = 0;
+    // create the map, indexed with u8 pair, the value is a pair: boolean and u16
+    let mut floor = Floor::new(size);
+    fill_floor(&mut floor, input, size)?;
+    trace::emit(Verbosity::Detail, || format!("day04: floor\n{}", floor.render(size)));
     // iterate through the whole floor map
     let positions_to_update: Vec<(Position, u16)> = floor.floor_map.iter()
         .filter(|(_, tile)| tile.is_filled)
@@ -134,7 +161,7 @@ pub fn day04_part1(filename: &str, size: u8) -> u64 {
     for (position, neighbors) in positions_to_update {
         floor.floor_map.get_mut(&position).unwrap().neighbors = neighbors;
     }
-    // floor.print_neighbors(size);
+    trace::emit(Verbosity::Detail, || format!("day04: neighbors\n{}", floor.render_neighbors(size)));
 
     // iterate through the whole floor map, again
     for (_position, tile) in floor.floor_map.iter() {
@@ -143,28 +170,23 @@ pub fn day04_part1(filename: &str, size: u8) -> u64 {
             result += 1;
         }
     }
-    result
+    Ok(result)
+}
+
+pub fn day04_part1_from_reader<R: BufRead>(reader: R, size: u8) -> Result<u64, AocError> {
+    day04_part1_from_str(&read_input(reader)?, size)
+}
+
+pub fn day04_part1(filename: &str, size: u8) -> Result<u64, AocError> {
+    day04_part1_from_reader(open_input(filename)?, size)
 }
 
-pub fn day04_part2(filename: &str, size: u8) -> u64 {
+pub fn day04_part2_from_str(input: &str, size: u8) -> Result<u64, AocError> {
     let mut result = 0;
     // create the map, indexed with u8 pair, the value is a pair: boolean and u16
     let mut floor = Floor::new(size);
-    // read the file, named 'filename'
-    let input = fs::read_to_string(filename).unwrap();
-    // for every line ...
-    for (line_idx, line) in input.lines().enumerate() {
-        // ... and the intersection with every column ...
-        for (column_idx, char) in line.chars().enumerate() {
-            // ... mark the boolean as full/empty
-            if char == '.' {
-                floor.floor_map.get_mut(&Position::new(column_idx as u8, line_idx as u8)).unwrap().is_filled = false;
-            } else {
-                floor.floor_map.get_mut(&Position::new(column_idx as u8, line_idx as u8)).unwrap().is_filled = true;
-            }
-        }
-    }
-    //floor.print(size);
+    fill_floor(&mut floor, input, size)?;
+    trace::emit(Verbosity::Detail, || format!("day04: floor\n{}", floor.render(size)));
     // iterate through the whole floor map
     let positions_to_update: Vec<(Position, u16)> = floor.floor_map.iter()
         .filter(|(_, tile)| tile.is_filled)
@@ -177,9 +199,9 @@ pub fn day04_part2(filename: &str, size: u8) -> u64 {
     for (position, neighbors) in positions_to_update {
         floor.floor_map.get_mut(&position).unwrap().neighbors = neighbors;
     }
-    // floor.print_neighbors(size);
+    trace::emit(Verbosity::Detail, || format!("day04: neighbors\n{}", floor.render_neighbors(size)));
 
-    loop {
+    for round in 1.. {
         // iterate through the whole floor map, again
         let positions_to_clear: Vec<Position> = floor.floor_map.iter()
             .filter(|(_, tile)| tile.is_filled && tile.neighbors < 4)
@@ -190,6 +212,7 @@ pub fn day04_part2(filename: &str, size: u8) -> u64 {
             break;
         }
 
+        trace::emit(Verbosity::Steps, || format!("day04: round {} removes {} rolls", round, positions_to_clear.len()));
         for position in positions_to_clear {
             result += 1;
             // clear this tile
@@ -197,36 +220,116 @@ pub fn day04_part2(filename: &str, size: u8) -> u64 {
             floor.floor_map.get_mut(&position).unwrap().neighbors = 0;
             Floor::inform_neighbors(&position, &mut floor.floor_map);
         }
+        trace::emit(Verbosity::Detail, || format!("day04: floor after round {}\n{}", round, floor.render(size)));
+    }
+
+    Ok(result)
+}
+
+pub fn day04_part2_from_reader<R: BufRead>(reader: R, size: u8) -> Result<u64, AocError> {
+    day04_part2_from_str(&read_input(reader)?, size)
+}
+
+pub fn day04_part2(filename: &str, size: u8) -> Result<u64, AocError> {
+    day04_part2_from_reader(open_input(filename)?, size)
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Answer = u64;
+
+    fn day(&self) -> u8 {
+        4
     }
 
-    result
+    fn title(&self) -> &'static str {
+        "Printing Department"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        let size = floor_size(input)?;
+        fill_floor(&mut Floor::new(size), input, size)
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        day04_part1_from_str(input, floor_size(input)?)
+    }
+
+    fn part2(&self, input: &str) -> Option<Result<Self::Answer, AocError>> {
+        Some(floor_size(input).and_then(|size| day04_part2_from_str(input, size)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::{with_tracer, MemorySink};
+    use crate::answers::expected;
+    use crate::Part;
+
+    const EXAMPLE: &str = "..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@\n@.@@@@..@.\n@@.@@@@.@@\n.@@@@@@@.@\n.@.@.@.@@@\n@.@@@.@@@@\n.@@@@@@@@.\n@.@.@@@.@.\n";
+
+    #[test]
+    fn day04_from_str() {
+        assert_eq!(day04_part1_from_str(EXAMPLE, 10).unwrap(), 13);
+        assert_eq!(day04_part2_from_str(EXAMPLE, 10).unwrap(), 43);
+    }
+
+    #[test]
+    fn day04_from_reader() {
+        assert_eq!(day04_part1_from_reader(EXAMPLE.as_bytes(), 10).unwrap(), 13);
+    }
 
     #[test]
     fn day04_part1_a() {
-        let result = day04_part1("input/day04a.txt", 10);
-        assert_eq!(result, 13);
+        let result = day04_part1("input/day04a.txt", 10).unwrap();
+        assert_eq!(result.to_string(), expected(4, Part::One, "day04a.txt"));
     }
 
     #[test]
     fn day04_part1_b() {
-        let result = day04_part1("input/day04b.txt", 139);
-        assert_eq!(result, 1474);
+        let result = day04_part1("input/day04b.txt", 139).unwrap();
+        assert_eq!(result.to_string(), expected(4, Part::One, "day04b.txt"));
     }
 
     #[test]
     fn day04_part2_a() {
-        let result = day04_part2("input/day04a.txt", 10);
-        assert_eq!(result, 43);
+        let result = day04_part2("input/day04a.txt", 10).unwrap();
+        assert_eq!(result.to_string(), expected(4, Part::Two, "day04a.txt"));
     }
 
     #[test]
     fn day04_part2_b() {
-        let result = day04_part2("input/day04b.txt", 139);
-        assert_eq!(result, 8910);
+        let result = day04_part2("input/day04b.txt", 139).unwrap();
+        assert_eq!(result.to_string(), expected(4, Part::Two, "day04b.txt"));
+    }
+
+    #[test]
+    fn day04_traces_removal_rounds() {
+        let sink = MemorySink::default();
+        let result = with_tracer(Verbosity::Steps, Box::new(sink.clone()), || day04_part2_from_str(EXAMPLE, 10)).unwrap();
+        let events = sink.events();
+        assert_eq!(events.first().map(String::as_str), Some("day04: round 1 removes 13 rolls"));
+        let removed = events.iter().map(|event| event.split_whitespace().nth(4).unwrap().parse::<u64>().unwrap()).sum::<u64>();
+        assert_eq!(removed, result);
+    }
+
+    #[test]
+    fn day04_traces_the_floor_in_detail() {
+        let sink = MemorySink::default();
+        with_tracer(Verbosity::Detail, Box::new(sink.clone()), || day04_part1_from_str("@@\n.@\n", 2)).unwrap();
+        assert_eq!(sink.events(), vec!["day04: floor\n@@\n.@\n", "day04: neighbors\n22\n02\n"]);
+    }
+
+    #[test]
+    fn day04_fill_reports_location() {
+        let mut floor = Floor::new(3);
+        let error = fill_floor(&mut floor, "..@\n@#.\n...", 3).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 2: unexpected tile '#'");
+        let error = fill_floor(&mut floor, "..@.\n...\n...", 3).err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 4: line is longer than 3 tiles");
+        let error = fill_floor(&mut floor, "", 3).err().unwrap();
+        assert_eq!(error.to_string(), "empty grid");
     }
 }
@@ -1,6 +1,9 @@
-use std::fs;
+use std::io::BufRead;
 use regex::Regex;
 
+use crate::trace::{self, Verbosity};
+use crate::{open_input, read_input, AocError, Solution};
+
 struct Range {
     start: u64,
     end: u64,
@@ -18,30 +21,45 @@ impl std::fmt::Debug for Range {
     }
 }
 
-pub fn day05_part1(filename: &str) -> u64 {
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
+fn parse_number(text: &str, line_idx: usize, column: usize) -> Result<u64, AocError> {
+    text.parse::<u64>()
+        .map_err(|_| AocError::malformed(line_idx + 1, column, format!("number out of range: {}", text)))
+}
 
+fn parse_database(input: &str) -> Result<(Vec<Range>, Vec<u64>), AocError> {
     let mut ranges: Vec<Range> = Vec::new();
     let mut numbers: Vec<u64> = Vec::new();
 
     // regex that represents two integers seperated with a hyphen
-    let re_range = Regex::new(r"(\d+)-(\d+)").unwrap();
+    let re_range = Regex::new(r"^(\d+)-(\d+)$").unwrap();
     // regex that represents a single integer
-    let re_number = Regex::new(r"(\d+)").unwrap();
+    let re_number = Regex::new(r"^(\d+)$").unwrap();
 
     // keep reading lines from the file, using an iterator until the end of the file
-    for line in input.lines() {
+    for (line_idx, line) in input.lines().enumerate() {
         // if the line matches the range regex, add the range to the ranges vector
-        if re_range.is_match(line) {
-            let caps = re_range.captures(line).unwrap();
-            ranges.push(Range::new(caps[1].parse::<u64>().unwrap(), caps[2].parse::<u64>().unwrap()));
+        if let Some(caps) = re_range.captures(line) {
+            let start = parse_number(&caps[1], line_idx, 1)?;
+            let end = parse_number(&caps[2], line_idx, caps.get(2).unwrap().start() + 1)?;
+            if start > end {
+                return Err(AocError::malformed(line_idx + 1, 1, format!("range starts after it ends: {}", line)));
+            }
+            ranges.push(Range::new(start, end));
         }
         // if the line matches the number regex, add the number to the numbers vector
         else if re_number.is_match(line) {
-            numbers.push(line.parse::<u64>().unwrap());
+            numbers.push(parse_number(line, line_idx, 1)?);
+        }
+        // the blank line separates the ranges from the numbers, anything else is an error
+        else if !line.trim().is_empty() {
+            return Err(AocError::malformed(line_idx + 1, 1, format!("expected a range or a number, found {:?}", line)));
         }
     }
+    Ok((ranges, numbers))
+}
+
+pub fn day05_part1_from_str(input: &str) -> Result<u64, AocError> {
+    let (ranges, numbers) = parse_database(input)?;
 
     let mut result = 0;
 
@@ -56,94 +74,164 @@ pub fn day05_part1(filename: &str) -> u64 {
             }
         }
     }
-    return result;
+    Ok(result)
 }
 
-pub fn day05_part2(filename: &str) -> u64 {
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
-
-    let mut ranges: Vec<Range> = Vec::new();
-    let mut numbers: Vec<u64> = Vec::new();
+pub fn day05_part1_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    day05_part1_from_str(&read_input(reader)?)
+}
 
-    // regex that represents two integers seperated with a hyphen
-    let re_range = Regex::new(r"(\d+)-(\d+)").unwrap();
-    // regex that represents a single integer
-    let re_number = Regex::new(r"(\d+)").unwrap();
+pub fn day05_part1(filename: &str) -> Result<u64, AocError> {
+    day05_part1_from_reader(open_input(filename)?)
+}
 
-    // keep reading lines from the file, using an iterator until the end of the file
-    for line in input.lines() {
-        // if the line matches the range regex, add the range to the ranges vector
-        if re_range.is_match(line) {
-            let caps = re_range.captures(line).unwrap();
-            ranges.push(Range::new(caps[1].parse::<u64>().unwrap(), caps[2].parse::<u64>().unwrap()));
-        }
-        // if the line matches the number regex, add the number to the numbers vector
-        else if re_number.is_match(line) {
-            numbers.push(line.parse::<u64>().unwrap());
-        }
-    }
+pub fn day05_part2_from_str(input: &str) -> Result<u64, AocError> {
+    let (mut ranges, _) = parse_database(input)?;
 
     // sort the ranges by start
     ranges.sort_by_key(|range| range.start);
 
     let mut merged_ranges: Vec<Range> = Vec::new();
-    let mut result = 0;
+    let mut result: u64 = 0;
 
     // iterate through all the ranges
     for range in ranges {
-        if merged_ranges.is_empty() {
-            merged_ranges.push(range);
-            continue;
-        }
-
-        // get the last merged ranges
-        let last_merged_range = merged_ranges.last_mut().unwrap();
-
-        // if the current range is shifted right of the merged range, but overlaps with it, merge them
-        if range.start <= last_merged_range.end && range.end > last_merged_range.end {
-            last_merged_range.end = range.end;
-        }
-        // if the current range includes the merged range, merge them
-        else if range.start > last_merged_range.end {
-            merged_ranges.push(range);
+        match merged_ranges.last_mut() {
+            // if the current range is shifted right of the merged range, but overlaps with it, merge them
+            Some(last_merged_range) if range.start <= last_merged_range.end && range.end > last_merged_range.end => {
+                trace::emit(Verbosity::Steps, || format!("day05: {:?} extends {:?}", range, last_merged_range));
+                last_merged_range.end = range.end;
+            }
+            Some(last_merged_range) if range.start <= last_merged_range.end => {
+                trace::emit(Verbosity::Steps, || format!("day05: {:?} is within {:?}", range, last_merged_range));
+            }
+            // the first range, or one right of the merged range, starts a new one
+            _ => {
+                trace::emit(Verbosity::Steps, || format!("day05: {:?} starts a new range", range));
+                merged_ranges.push(range);
+            }
         }
     }
 
     // iterate through all the merged ranges
     for merged_range in merged_ranges {
-        result += merged_range.end - merged_range.start + 1;
+        // the ends are in order, but 0-18446744073709551615 alone has one ID more than a u64 holds
+        let ids = (merged_range.end - merged_range.start).checked_add(1);
+        result = ids.and_then(|ids| result.checked_add(ids)).ok_or_else(|| AocError::overflow("the count of fresh IDs"))?;
     }
 
-    result
+    Ok(result)
+}
+
+pub fn day05_part2_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    day05_part2_from_str(&read_input(reader)?)
 }
 
+pub fn day05_part2(filename: &str) -> Result<u64, AocError> {
+    day05_part2_from_reader(open_input(filename)?)
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Answer = u64;
+
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "Cafeteria"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        parse_database(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        day05_part1_from_str(input)
+    }
+
+    fn part2(&self, input: &str) -> Option<Result<Self::Answer, AocError>> {
+        Some(day05_part2_from_str(input))
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::{with_tracer, MemorySink};
+    use crate::answers::expected;
+    use crate::Part;
+
+    const EXAMPLE: &str = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+
+    #[test]
+    fn day05_from_str() {
+        assert_eq!(day05_part1_from_str(EXAMPLE).unwrap(), 3);
+        assert_eq!(day05_part2_from_str(EXAMPLE).unwrap(), 14);
+    }
+
+    #[test]
+    fn day05_from_reader() {
+        assert_eq!(day05_part1_from_reader(EXAMPLE.as_bytes()).unwrap(), 3);
+    }
 
     #[test]
     fn day05_part1_a() {
-        let result = day05_part1("input/day05a.txt");
-        assert_eq!(result, 3);
+        let result = day05_part1("input/day05a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(5, Part::One, "day05a.txt"));
     }
 
     #[test]
     fn day05_part1_b() {
-        let result = day05_part1("input/day05b.txt");
-        assert_eq!(result, 770);
+        let result = day05_part1("input/day05b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(5, Part::One, "day05b.txt"));
     }
 
     #[test]
     fn day05_part2_a() {
-        let result = day05_part2("input/day05a.txt");
-        assert_eq!(result, 14);
+        let result = day05_part2("input/day05a.txt").unwrap();
+        assert_eq!(result.to_string(), expected(5, Part::Two, "day05a.txt"));
     }
 
     #[test]
     fn day05_part2_b() {
-        let result = day05_part2("input/day05b.txt");
-        assert_eq!(result, 357674099117260);
+        let result = day05_part2("input/day05b.txt").unwrap();
+        assert_eq!(result.to_string(), expected(5, Part::Two, "day05b.txt"));
+    }
+
+    #[test]
+    fn day05_traces_range_merges() {
+        let sink = MemorySink::default();
+        with_tracer(Verbosity::Steps, Box::new(sink.clone()), || day05_part2_from_str(EXAMPLE)).unwrap();
+        assert_eq!(
+            sink.events(),
+            vec![
+                "day05: 3..5 starts a new range",
+                "day05: 10..14 starts a new range",
+                "day05: 12..18 extends 10..14",
+                "day05: 16..20 extends 10..18"
+            ]
+        );
+    }
+
+    #[test]
+    fn day05_parse_reports_location() {
+        let error = parse_database("3-5\n10-14\n\n1\nfive").err().unwrap();
+        assert_eq!(error.to_string(), "line 5, column 1: expected a range or a number, found \"five\"");
+        let error = parse_database("3-99999999999999999999").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 3: number out of range: 99999999999999999999");
+        let error = parse_database("3-5\n10-14\n5-3").err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 1: range starts after it ends: 5-3");
+    }
+
+    #[test]
+    fn day05_count_beyond_u64() {
+        assert_eq!(day05_part2_from_str("0-18446744073709551614").unwrap(), u64::MAX);
+        let error = day05_part2_from_str("0-18446744073709551615").err().unwrap();
+        assert_eq!(error.to_string(), "arithmetic overflow: the count of fresh IDs");
+        let error = day05_part2_from_str("0-9223372036854775807\n9223372036854775808-18446744073709551615").err().unwrap();
+        assert_eq!(error.to_string(), "arithmetic overflow: the count of fresh IDs");
     }
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
-use std::fs;
+use std::io::BufRead;
+
+use crate::{open_input, read_input, AocError, Part, Solution};
 
 enum Operation {
     Add,
@@ -6,35 +8,61 @@ enum Operation {
 }
 
 impl Operation {
-    fn new(operation: &str) -> Self {
+    fn new(operation: &str, line: usize, column: usize) -> Result<Self, AocError> {
         match operation {
-            "+" => Self::Add,
-            "*" => Self::Multiply,
-            _ => panic!("Invalid operation: {}", operation),
+            "+" => Ok(Self::Add),
+            "*" => Ok(Self::Multiply),
+            _ => Err(AocError::malformed(line, column, format!("invalid operation: {}", operation))),
         }
     }
 }
 
-fn day06_part1(filename: &str) -> u64 {
-    let mut result = 0;
-
-    // read the input file
-    let input = fs::read_to_string(filename).unwrap();
+// split the line on whitespace, but keep the (1-based) column of every token
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (idx, char) in line.char_indices().chain(std::iter::once((line.len(), ' '))) {
+        match (start, char.is_whitespace()) {
+            (None, false) => start = Some(idx),
+            (Some(token_start), true) => {
+                tokens.push((token_start + 1, &line[token_start..idx]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
 
+// the numbers, transposed so that every row is one problem, and the operation of every problem
+fn parse_worksheet(input: &str) -> Result<(Vec<Vec<u64>>, Vec<Operation>), AocError> {
     // split the input into lines
     let lines = input.lines().collect::<Vec<&str>>();
+    if lines.len() < 2 {
+        return Err(AocError::domain("the worksheet needs at least one row of numbers and the operations"));
+    }
 
     // separate the last line from the rest
     let last_line = lines.last().unwrap();
     let initial_lines = &lines[..lines.len() - 1];
 
     // create the 2D matrix of numbers
-    let mut matrix = Vec::new();
+    let mut matrix: Vec<Vec<u64>> = Vec::new();
 
     // iterate over the initial lines
-    for line in initial_lines {
+    for (line_idx, line) in initial_lines.iter().enumerate() {
         // split the line into numbers
-        let numbers = line.split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect::<Vec<_>>();
+        let mut numbers = Vec::new();
+        for (column, token) in tokens(line) {
+            let number = token
+                .parse::<u64>()
+                .map_err(|_| AocError::malformed(line_idx + 1, column, format!("invalid number: {}", token)))?;
+            numbers.push(number);
+        }
+        // every row has to have as many numbers as the first one
+        if !matrix.is_empty() && numbers.len() != matrix[0].len() {
+            return Err(AocError::malformed(line_idx + 1, line.len() + 1, format!("expected {} numbers, found {}", matrix[0].len(), numbers.len())));
+        }
         // add the vector to the matrix
         matrix.push(numbers);
     }
@@ -43,44 +71,123 @@ fn day06_part1(filename: &str) -> u64 {
     let transposed_matrix = (0..matrix[0].len())
         .map(|col_idx| matrix.iter().map(|row| row[col_idx]).collect::<Vec<u64>>())
         .collect::<Vec<Vec<u64>>>();
-    
+
     // parse the last line into operations
-    let operations = last_line.split_whitespace().map(|x| Operation::new(x)).collect::<Vec<_>>();
+    let mut operations = Vec::new();
+    for (column, token) in tokens(last_line) {
+        operations.push(Operation::new(token, lines.len(), column)?);
+    }
+    if operations.len() != transposed_matrix.len() {
+        return Err(AocError::malformed(lines.len(), last_line.len() + 1, format!("expected {} operations, found {}", transposed_matrix.len(), operations.len())));
+    }
+    Ok((transposed_matrix, operations))
+}
+
+pub fn day06_part1_from_str(input: &str) -> Result<u64, AocError> {
+    let mut result: u64 = 0;
+    let (transposed_matrix, operations) = parse_worksheet(input)?;
 
     // iterate throught the transposed matrix row by row
     for (i, row) in transposed_matrix.iter().enumerate() {
         let operation = &operations[i];
 
         // iterate through the row
-        let mut row_result = match operation {
+        let mut row_result: u64 = match operation {
             Operation::Add => 0,
             Operation::Multiply => 1,
         };
 
         for number in row {
-            match operation {
-                Operation::Add => row_result += number,
-                Operation::Multiply => row_result *= number,
-            }
+            let next = match operation {
+                Operation::Add => row_result.checked_add(*number),
+                Operation::Multiply => row_result.checked_mul(*number),
+            };
+            row_result = next.ok_or_else(|| AocError::overflow(format!("the result of problem {}", i + 1)))?;
         }
-        
-        result += row_result;
+
+        result = result.checked_add(row_result).ok_or_else(|| AocError::overflow("the grand total"))?;
     }
 
-    result
+    Ok(result)
+}
+
+pub fn day06_part1_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    day06_part1_from_str(&read_input(reader)?)
+}
+
+pub fn day06_part1(filename: &str) -> Result<u64, AocError> {
+    day06_part1_from_reader(open_input(filename)?)
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Answer = u64;
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Trash Compactor"
+    }
+
+    fn parts(&self) -> &'static [Part] {
+        &[Part::One]
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        parse_worksheet(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        day06_part1_from_str(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers::expected;
+
+    const EXAMPLE: &str = "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   +  \n";
+
+    #[test]
+    fn day06_from_str() {
+        assert_eq!(day06_part1_from_str(EXAMPLE).unwrap(), 4277556);
+    }
+
+    #[test]
+    fn day06_from_reader() {
+        assert_eq!(day06_part1_from_reader(EXAMPLE.as_bytes()).unwrap(), 4277556);
+    }
 
     #[test]
     fn day06_part1a_test() {
-        assert_eq!(day06_part1("input/day06a.txt"), 4277556);
+        assert_eq!(day06_part1("input/day06a.txt").unwrap().to_string(), expected(6, Part::One, "day06a.txt"));
     }
 
     #[test]
     fn day06_part1b_test() {
-        assert_eq!(day06_part1("input/day06b.txt"), 6417439773370);
+        assert_eq!(day06_part1("input/day06b.txt").unwrap().to_string(), expected(6, Part::One, "day06b.txt"));
+    }
+
+    #[test]
+    fn day06_tokens_keep_columns() {
+        assert_eq!(tokens(" 45 64  387 "), vec![(2, "45"), (5, "64"), (9, "387")]);
+    }
+
+    #[test]
+    fn day06_overflow() {
+        let error = day06_part1_from_str("1 4294967296\n2 4294967296\n+ *").err().unwrap();
+        assert_eq!(error.to_string(), "arithmetic overflow: the result of problem 2");
+        let error = day06_part1_from_str("18446744073709551615 1\n+ +").err().unwrap();
+        assert_eq!(error.to_string(), "arithmetic overflow: the grand total");
+    }
+
+    #[test]
+    fn day06_invalid_operation() {
+        let error = Operation::new("-", 4, 9).err().unwrap();
+        assert_eq!(error.to_string(), "line 4, column 9: invalid operation: -");
     }
 }
@@ -0,0 +1,60 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum AocError {
+    // the input could not be read (or written, or downloaded) at all
+    Io(io::Error),
+    // a line of the input does not follow the expected format, both line and column are 1-based
+    Malformed { line: usize, column: usize, message: String },
+    // the input is well-formed, but the puzzle cannot be solved for it (e.g. an empty grid)
+    Domain(String),
+    // a result (or an intermediate value) does not fit the integer type it is computed in, the string tells which one
+    Overflow(String),
+    // the puzzle server answered with something else than a success
+    Http { status: u16, message: String },
+    // an answer was submitted too recently, another one may be sent after the given number of seconds
+    RateLimited { wait_secs: u64 },
+}
+
+impl AocError {
+    pub fn malformed(line: usize, column: usize, message: impl Into<String>) -> Self {
+        AocError::Malformed { line, column, message: message.into() }
+    }
+
+    pub fn domain(message: impl Into<String>) -> Self {
+        AocError::Domain(message.into())
+    }
+
+    pub fn overflow(what: impl Into<String>) -> Self {
+        AocError::Overflow(what.into())
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::Io(error) => write!(f, "I/O error: {}", error),
+            AocError::Malformed { line, column, message } => write!(f, "line {}, column {}: {}", line, column, message),
+            AocError::Domain(message) => write!(f, "{}", message),
+            AocError::Overflow(what) => write!(f, "arithmetic overflow: {}", what),
+            AocError::Http { status, message } => write!(f, "server responded with {}: {}", status, message),
+            AocError::RateLimited { wait_secs } => write!(f, "answer submitted too recently, wait {}s", wait_secs),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(error: io::Error) -> Self {
+        AocError::Io(error)
+    }
+}
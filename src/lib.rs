@@ -1,6 +1,223 @@
+pub mod answers;
+pub mod bench;
+pub mod client;
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
-pub mod day06;
\ No newline at end of file
+pub mod day06;
+pub mod error;
+pub mod mock_server;
+pub mod params;
+#[cfg(test)]
+mod property;
+pub mod results;
+pub mod scaffold;
+pub mod trace;
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+
+pub use error::AocError;
+pub use params::Params;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Invalid part: {}", s)),
+        }
+    }
+}
+
+// the solvers work on the whole input text, no matter where it comes from
+pub fn read_input<R: BufRead>(mut reader: R) -> Result<String, AocError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+pub fn open_input(filename: &str) -> Result<BufReader<File>, AocError> {
+    Ok(BufReader::new(File::open(filename)?))
+}
+
+// every day implements this trait, so the puzzles can be discovered and run uniformly
+pub trait Solution {
+    type Answer: fmt::Display;
+
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    // not every day has its second part solved (yet)
+    fn parts(&self) -> &'static [Part] {
+        &Part::ALL
+    }
+
+    // just the parsing (and validation) of the input, so that its cost can be told apart from solving
+    fn parse(&self, _input: &str) -> Result<(), AocError> {
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError>;
+
+    fn part2(&self, _input: &str) -> Option<Result<Self::Answer, AocError>> {
+        None
+    }
+
+    // the names of the parameters the day can be tuned with, days without any never see them
+    fn params(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn part1_with(&self, input: &str, _params: &Params) -> Result<Self::Answer, AocError> {
+        self.part1(input)
+    }
+
+    fn part2_with(&self, input: &str, _params: &Params) -> Option<Result<Self::Answer, AocError>> {
+        self.part2(input)
+    }
+}
+
+// type-erased view of a solution, so that days with different answer types can share one registry
+pub trait AnySolution: Sync {
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    fn parts(&self) -> &'static [Part];
+
+    fn parse(&self, input: &str) -> Result<(), AocError>;
+
+    fn params(&self) -> &'static [&'static str];
+
+    fn run(&self, part: Part, input: &str) -> Option<Result<String, AocError>> {
+        self.run_with(part, input, &Params::default())
+    }
+
+    fn run_with(&self, part: Part, input: &str, params: &Params) -> Option<Result<String, AocError>>;
+}
+
+impl<S: Solution + Sync> AnySolution for S {
+    fn day(&self) -> u8 {
+        Solution::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Solution::title(self)
+    }
+
+    fn parts(&self) -> &'static [Part] {
+        Solution::parts(self)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), AocError> {
+        Solution::parse(self, input)
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        Solution::params(self)
+    }
+
+    fn run_with(&self, part: Part, input: &str, params: &Params) -> Option<Result<String, AocError>> {
+        if let Some(key) = params.keys().find(|key| !Solution::params(self).contains(key)) {
+            return Some(Err(AocError::domain(format!("day {} has no parameter {}", Solution::day(self), key))));
+        }
+        match part {
+            Part::One => Some(self.part1_with(input, params).map(|answer| answer.to_string())),
+            Part::Two => self.part2_with(input, params).map(|result| result.map(|answer| answer.to_string())),
+        }
+    }
+}
+
+// all the implemented days, in order
+static SOLUTIONS: &[&dyn AnySolution] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+];
+
+pub fn solutions() -> &'static [&'static dyn AnySolution] {
+    SOLUTIONS
+}
+
+pub fn find(day: u8) -> Option<&'static dyn AnySolution> {
+    SOLUTIONS.iter().copied().find(|solution| solution.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_days_are_unique_and_ordered() {
+        let days = solutions().iter().map(|solution| solution.day()).collect::<Vec<u8>>();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(&days[..6], &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn registry_finds_a_day() {
+        let solution = find(3).unwrap();
+        assert_eq!(solution.title(), "Lobby");
+        let input = "987654321111111\n811111111111119\n234234234234278\n818181911112111";
+        assert_eq!(solution.run(Part::One, input).unwrap().unwrap(), "357");
+        assert!(find(25).is_none());
+    }
+
+    #[test]
+    fn registry_reports_missing_parts() {
+        let solution = find(6).unwrap();
+        assert_eq!(solution.parts(), &[Part::One]);
+        assert!(solution.run(Part::Two, "1 2\n+ *").is_none());
+    }
+
+    #[test]
+    fn registry_rejects_unknown_params() {
+        let solution = find(3).unwrap();
+        let params = "size=10".parse::<Params>().unwrap();
+        let error = solution.run_with(Part::One, "12", &params).unwrap().err().unwrap();
+        assert_eq!(error.to_string(), "day 3 has no parameter size");
+        assert_eq!(find(1).unwrap().params(), &["size", "start"]);
+    }
+
+    #[test]
+    fn open_input_reports_missing_file() {
+        let result = open_input("input/day01_missing.txt");
+        assert!(matches!(result, Err(AocError::Io(_))));
+    }
+
+    #[test]
+    fn read_input_from_any_reader() {
+        let input = read_input("L68\nL30\n".as_bytes()).unwrap();
+        assert_eq!(input, "L68\nL30\n");
+    }
+}
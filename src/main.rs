@@ -0,0 +1,717 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use aoc_2025::answers::{self, Manifest, Outcome};
+use aoc_2025::bench::{self, Baseline, Measurement};
+use aoc_2025::client::{self, Client, Verdict};
+use aoc_2025::day01;
+use aoc_2025::day02::{self, Base, Rule};
+use aoc_2025::mock_server::{MockServer, Puzzles};
+use aoc_2025::results::{run_timed, RunResult};
+use aoc_2025::scaffold::scaffold;
+use aoc_2025::trace::{self, FileSink, StderrSink, StdoutSink, TraceSink, Verbosity};
+use aoc_2025::{find, open_input, read_input, solutions, AnySolution, AocError, Params, Part};
+
+const USAGE: &str = "usage:
+    aoc list
+    aoc run <day> [--part 1|2] [--input FILE|-] [--set KEY=VALUE,...] [--json] [--verbosity LEVEL] [--trace-file FILE]
+    aoc all [--input-dir DIR] [--suffix SUFFIX] [--json] [--verbosity LEVEL] [--trace-file FILE]
+    aoc verify [--answers FILE] [--input-dir DIR] [--day N]
+    aoc stream [--input FILE|-] [--set KEY=VALUE,...]
+    aoc render [--input FILE|-] [--set KEY=VALUE,...] [--width N] [--delay MS] [--svg FILE]
+    aoc report [--part 1|2] [--rule RULE] [--base N] [--input FILE|-] [--limit N]
+    aoc inverse <part> <target> [--input FILE|-] [--set KEY=VALUE,...] [--max-edits N]
+    aoc bench [--day N] [--part 1|2] [--runs N] [--warmup N] [--input-dir DIR] [--suffix SUFFIX]
+              [--save FILE] [--baseline FILE] [--tolerance PERCENT]
+    aoc new <day> [--title TITLE]
+    aoc fetch <day> [--server URL] [--session TOKEN] [--input-dir DIR]
+    aoc submit <day> <part> [--answer ANSWER] [--server URL] [--session TOKEN] [--input-dir DIR]
+    aoc serve [--port PORT] [--session TOKEN] [--answers FILE] [--input-dir DIR]
+
+by default the real input (input/dayNNb.txt) is used, '-' reads the input from stdin
+--set tunes the days that have parameters (see list), e.g. --set size=100,start=50 for day 1
+--json prints one JSON object per part: day, part, input, answer, elapsed_us and error
+--verbosity quiet|steps|detail traces what the solvers do, to stdout (stderr with --json) or to --trace-file
+stream solves both parts of day 1 line by line, keeping one line (up to 1 MiB) in memory, and reports the throughput
+render draws a frame of the day 1 dial per step (--width, 60 columns), and the whole run as SVG with --svg
+report breaks day 2 down by range: the count, the sum and (up to --limit, 10, per range) the IDs with their patterns
+--rule (or --set rule=RULE for run) picks the invalid IDs of day 2: x2 (part 1), x2+ (part 2), x2-4, palindrome, near-repeat
+--base (or --set base=N) looks for them in the digits of the IDs in a base from 2 to 36, the input stays decimal
+inverse finds the fewest day 1 instructions to change (at most --max-edits, 3) for the part to have the target answer
+bench reports the median run time and fails when it is more than --tolerance (10%) above the baseline
+fetch and submit speak plain http only, to the mock server of serve (--server, http://127.0.0.1:8025), not to https
+the session token defaults to the AOC_SESSION environment variable";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    List,
+    Run { day: u8, part: Option<Part>, input: Option<String>, params: Params, json: bool, trace: Trace },
+    All { input_dir: String, suffix: String, json: bool, trace: Trace },
+    Verify { answers: String, input_dir: String, day: Option<u8> },
+    Stream { input: Option<String>, params: Params },
+    Render { input: Option<String>, params: Params, width: usize, delay: Option<u64>, svg: Option<String> },
+    Report { part: Option<Part>, rule: Option<Rule>, base: Base, input: Option<String>, limit: usize },
+    Inverse { part: Part, target: i64, input: Option<String>, params: Params, max_edits: usize },
+    Bench(Bench),
+    New { day: u8, title: String },
+    Fetch { day: u8, server: String, session: String, input_dir: String },
+    Submit { day: u8, part: Part, answer: Option<String>, server: String, session: String, input_dir: String },
+    Serve { port: u16, session: String, answers: String, input_dir: String },
+}
+
+// where the step events of the solvers go, nowhere by default
+#[derive(Debug, Default, PartialEq)]
+struct Trace {
+    verbosity: Option<Verbosity>,
+    file: Option<String>,
+}
+
+impl Trace {
+    // with --json, stdout is left to the JSON lines and the events go to stderr
+    fn run<T>(&self, json: bool, f: impl FnOnce() -> T) -> Result<T, String> {
+        // a trace file alone asks for the step events
+        let verbosity = match (self.verbosity, &self.file) {
+            (Some(verbosity), _) => verbosity,
+            (None, Some(_)) => Verbosity::Steps,
+            (None, None) => return Ok(f()),
+        };
+        let sink: Box<dyn TraceSink> = match &self.file {
+            Some(file) => Box::new(FileSink::create(file).map_err(|error| format!("cannot create {}: {}", file, error))?),
+            None if json => Box::new(StderrSink),
+            None => Box::new(StdoutSink),
+        };
+        Ok(trace::with_tracer(verbosity, sink, f))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Bench {
+    day: Option<u8>,
+    part: Option<Part>,
+    runs: usize,
+    warmup: usize,
+    input_dir: String,
+    suffix: String,
+    save: Option<String>,
+    baseline: Option<String>,
+    tolerance: f64,
+}
+
+// the "--name value" pairs and "--name" flags following the positional arguments of a command
+struct Options<'a> {
+    values: HashMap<&'a str, &'a str>,
+    flags: Vec<&'a str>,
+}
+
+impl<'a> Options<'a> {
+    fn parse(args: &'a [String], known: &[&str]) -> Result<Self, String> {
+        Self::parse_with_flags(args, known, &[])
+    }
+
+    fn parse_with_flags(args: &'a [String], known: &[&str], known_flags: &[&str]) -> Result<Self, String> {
+        let mut values = HashMap::new();
+        let mut flags = Vec::new();
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            if known_flags.contains(&option.as_str()) {
+                flags.push(option.as_str());
+                continue;
+            }
+            if !known.contains(&option.as_str()) {
+                return Err(format!("unexpected argument: {}", option));
+            }
+            let value = args.next().ok_or_else(|| format!("missing value for {}", option))?;
+            values.insert(option.as_str(), value.as_str());
+        }
+        Ok(Self { values, flags })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.values.get(name).map(|value| value.to_string())
+    }
+
+    fn get_or(&self, name: &str, default: &str) -> String {
+        self.get(name).unwrap_or_else(|| default.to_string())
+    }
+
+    fn parsed<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.values.get(name) {
+            Some(value) => value.parse::<T>().map(Some).map_err(|_| format!("invalid value for {}: {}", name, value)),
+            None => Ok(None),
+        }
+    }
+
+    fn trace(&self) -> Result<Trace, String> {
+        Ok(Trace { verbosity: self.parsed("--verbosity")?, file: self.get("--trace-file") })
+    }
+
+    fn session(&self) -> Result<String, String> {
+        self.get("--session")
+            .or_else(|| env::var("AOC_SESSION").ok())
+            .ok_or_else(|| "missing session token (--session or AOC_SESSION)".to_string())
+    }
+}
+
+fn parse_day(arg: Option<&String>) -> Result<u8, String> {
+    let day = arg.ok_or("missing day")?;
+    day.parse::<u8>().map_err(|_| format!("invalid day: {}", day))
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+    match command.as_str() {
+        "list" => {
+            Options::parse(rest, &[])?;
+            Ok(Command::List)
+        }
+        "run" => {
+            let day = parse_day(rest.first())?;
+            let options = Options::parse_with_flags(&rest[1..], &["--part", "--input", "--set", "--verbosity", "--trace-file"], &["--json"])?;
+            Ok(Command::Run {
+                day,
+                part: options.parsed("--part")?,
+                input: options.get("--input"),
+                params: options.parsed("--set")?.unwrap_or_default(),
+                json: options.flag("--json"),
+                trace: options.trace()?,
+            })
+        }
+        "all" => {
+            let options = Options::parse_with_flags(rest, &["--input-dir", "--suffix", "--verbosity", "--trace-file"], &["--json"])?;
+            Ok(Command::All {
+                input_dir: options.get_or("--input-dir", "input"),
+                suffix: options.get_or("--suffix", "b"),
+                json: options.flag("--json"),
+                trace: options.trace()?,
+            })
+        }
+        "verify" => {
+            let options = Options::parse(rest, &["--answers", "--input-dir", "--day"])?;
+            Ok(Command::Verify {
+                answers: options.get_or("--answers", answers::DEFAULT_MANIFEST),
+                input_dir: options.get_or("--input-dir", "input"),
+                day: options.parsed("--day")?,
+            })
+        }
+        "stream" => {
+            let options = Options::parse(rest, &["--input", "--set"])?;
+            Ok(Command::Stream { input: options.get("--input"), params: options.parsed("--set")?.unwrap_or_default() })
+        }
+        "render" => {
+            let options = Options::parse(rest, &["--input", "--set", "--width", "--delay", "--svg"])?;
+            Ok(Command::Render {
+                input: options.get("--input"),
+                params: options.parsed("--set")?.unwrap_or_default(),
+                width: options.parsed("--width")?.unwrap_or(60),
+                delay: options.parsed("--delay")?,
+                svg: options.get("--svg"),
+            })
+        }
+        "report" => {
+            let options = Options::parse(rest, &["--part", "--rule", "--base", "--input", "--limit"])?;
+            Ok(Command::Report {
+                part: options.parsed("--part")?,
+                rule: options.parsed("--rule")?,
+                base: options.parsed("--base")?.unwrap_or(Base::DECIMAL),
+                input: options.get("--input"),
+                limit: options.parsed("--limit")?.unwrap_or(10),
+            })
+        }
+        "inverse" => {
+            let part = rest.first().ok_or("missing part")?.parse::<Part>()?;
+            let target = rest.get(1).ok_or("missing target")?;
+            let target = target.parse::<i64>().map_err(|_| format!("invalid target: {}", target))?;
+            let options = Options::parse(&rest[2..], &["--input", "--set", "--max-edits"])?;
+            Ok(Command::Inverse {
+                part,
+                target,
+                input: options.get("--input"),
+                params: options.parsed("--set")?.unwrap_or_default(),
+                max_edits: options.parsed("--max-edits")?.unwrap_or(3),
+            })
+        }
+        "bench" => {
+            let options = Options::parse(
+                rest,
+                &["--day", "--part", "--runs", "--warmup", "--input-dir", "--suffix", "--save", "--baseline", "--tolerance"],
+            )?;
+            Ok(Command::Bench(Bench {
+                day: options.parsed("--day")?,
+                part: options.parsed("--part")?,
+                runs: options.parsed("--runs")?.unwrap_or(10),
+                warmup: options.parsed("--warmup")?.unwrap_or(2),
+                input_dir: options.get_or("--input-dir", "input"),
+                suffix: options.get_or("--suffix", "b"),
+                save: options.get("--save"),
+                baseline: options.get("--baseline"),
+                tolerance: options.parsed("--tolerance")?.unwrap_or(10.0),
+            }))
+        }
+        "new" => {
+            let day = parse_day(rest.first())?;
+            let options = Options::parse(&rest[1..], &["--title"])?;
+            Ok(Command::New { day, title: options.get_or("--title", &format!("Day {}", day)) })
+        }
+        "fetch" => {
+            let day = parse_day(rest.first())?;
+            let options = Options::parse(&rest[1..], &["--server", "--session", "--input-dir"])?;
+            Ok(Command::Fetch {
+                day,
+                server: options.get_or("--server", client::DEFAULT_SERVER),
+                session: options.session()?,
+                input_dir: options.get_or("--input-dir", "input"),
+            })
+        }
+        "submit" => {
+            let day = parse_day(rest.first())?;
+            let part = rest.get(1).ok_or("missing part")?.parse::<Part>()?;
+            let options = Options::parse(&rest[2..], &["--answer", "--server", "--session", "--input-dir"])?;
+            Ok(Command::Submit {
+                day,
+                part,
+                answer: options.get("--answer"),
+                server: options.get_or("--server", client::DEFAULT_SERVER),
+                session: options.session()?,
+                input_dir: options.get_or("--input-dir", "input"),
+            })
+        }
+        "serve" => {
+            let options = Options::parse(rest, &["--port", "--session", "--answers", "--input-dir"])?;
+            Ok(Command::Serve {
+                port: options.parsed("--port")?.unwrap_or(8025),
+                session: options.session()?,
+                answers: options.get_or("--answers", answers::DEFAULT_MANIFEST),
+                input_dir: options.get_or("--input-dir", "input"),
+            })
+        }
+        _ => Err(format!("unknown command: {}", command)),
+    }
+}
+
+fn default_input(input_dir: &str, day: u8, suffix: &str) -> String {
+    format!("{}/day{:02}{}.txt", input_dir, day, suffix)
+}
+
+// '-' stands for stdin
+fn load_input(source: &str) -> Result<String, AocError> {
+    if source == "-" {
+        read_input(io::stdin().lock())
+    } else {
+        read_input(open_input(source)?)
+    }
+}
+
+fn print_result(result: &RunResult, json: bool) {
+    if json {
+        println!("{}", result.to_json());
+        return;
+    }
+    match (&result.answer, &result.error) {
+        (Some(answer), _) => println!("Day {:02} part {}: {}", result.day, result.part, answer),
+        (None, Some(error)) => eprintln!("Day {:02} part {}: error in {}: {}", result.day, result.part, result.input, error),
+        (None, None) => println!("Day {:02} part {}: not solved", result.day, result.part),
+    }
+}
+
+// returns the number of parts that failed
+fn run_parts(solution: &dyn AnySolution, parts: &[Part], source: &str, params: &Params, json: bool) -> usize {
+    let results = match load_input(source) {
+        Ok(input) => parts.iter().map(|part| run_timed(solution, *part, source, &input, params)).collect::<Vec<RunResult>>(),
+        Err(error) => parts.iter().map(|part| RunResult::failed(solution.day(), *part, source, &error)).collect(),
+    };
+    for result in &results {
+        print_result(result, json);
+    }
+    results.iter().filter(|result| result.error.is_some()).count()
+}
+
+// returns the number of parts that failed or regressed
+fn run_bench(options: Bench) -> Result<usize, String> {
+    let baseline = match &options.baseline {
+        Some(filename) => Some(Baseline::load(filename).map_err(|error| format!("cannot load {}: {}", filename, error))?),
+        None => None,
+    };
+    let mut failures = 0;
+    let mut measurements: Vec<Measurement> = Vec::new();
+    for solution in solutions().iter().filter(|solution| options.day.is_none_or(|day| solution.day() == day)) {
+        let source = default_input(&options.input_dir, solution.day(), &options.suffix);
+        let input = match load_input(&source) {
+            Ok(input) => input,
+            Err(error) => {
+                eprintln!("Day {:02}: cannot read {}: {}", solution.day(), source, error);
+                failures += 1;
+                continue;
+            }
+        };
+        for part in solution.parts().iter().filter(|part| options.part.is_none_or(|only| **part == only)) {
+            let measurement = match bench::measure(*solution, *part, &input, options.warmup, options.runs) {
+                Some(Ok(measurement)) => measurement,
+                Some(Err(error)) => {
+                    eprintln!("Day {:02} part {}: error in {}: {}", solution.day(), part, source, error);
+                    failures += 1;
+                    continue;
+                }
+                None => continue,
+            };
+            println!("{}", measurement);
+            if let Some(change) = baseline.as_ref().and_then(|baseline| baseline.compare(&measurement)) {
+                if change.is_regression(options.tolerance) {
+                    eprintln!("Day {:02} part {}: regressed by {:.1}% (baseline {:?})", measurement.day, measurement.part, change.percent(), change.before);
+                    failures += 1;
+                } else {
+                    println!("    {:+.1}% against the baseline ({:?})", change.percent(), change.before);
+                }
+            }
+            measurements.push(measurement);
+        }
+    }
+    if let Some(filename) = &options.save {
+        Baseline::from_measurements(&measurements).save(filename).map_err(|error| format!("cannot save {}: {}", filename, error))?;
+        println!("saved the baseline to {}", filename);
+    }
+    Ok(failures)
+}
+
+fn execute(command: Command) -> Result<usize, String> {
+    let mut failures = 0;
+    match command {
+        Command::List => {
+            for solution in solutions() {
+                let parts = solution.parts().iter().map(Part::to_string).collect::<Vec<String>>();
+                if solution.params().is_empty() {
+                    println!("Day {:02}: {} (parts: {})", solution.day(), solution.title(), parts.join(", "));
+                } else {
+                    println!("Day {:02}: {} (parts: {}; params: {})", solution.day(), solution.title(), parts.join(", "), solution.params().join(", "));
+                }
+            }
+        }
+        Command::Run { day, part, input, params, json, trace } => {
+            let solution = find(day).ok_or_else(|| format!("day {} is not implemented", day))?;
+            let parts = match part {
+                Some(part) => vec![part],
+                None => solution.parts().to_vec(),
+            };
+            let source = input.unwrap_or_else(|| default_input("input", day, "b"));
+            failures += trace.run(json, || run_parts(solution, &parts, &source, &params, json))?;
+        }
+        Command::All { input_dir, suffix, json, trace } => {
+            failures += trace.run(json, || {
+                solutions()
+                    .iter()
+                    .map(|solution| run_parts(*solution, solution.parts(), &default_input(&input_dir, solution.day(), &suffix), &Params::default(), json))
+                    .sum::<usize>()
+            })?;
+        }
+        Command::Verify { answers, input_dir, day } => {
+            let manifest = Manifest::load(&answers).map_err(|error| format!("cannot load {}: {}", answers, error))?;
+            let checks = answers::verify(&manifest, &input_dir, day).map_err(|error| error.to_string())?;
+            let (mut passed, mut missing) = (0, 0);
+            for check in &checks {
+                println!("{}", check);
+                match check.outcome {
+                    Outcome::Pass => passed += 1,
+                    Outcome::Missing { .. } => missing += 1,
+                    _ => failures += 1,
+                }
+            }
+            println!("{} passed, {} failed, {} missing", passed, failures, missing);
+        }
+        Command::Stream { input, params } => {
+            let source = input.unwrap_or_else(|| default_input("input", 1, "b"));
+            let evaluation = if source == "-" {
+                day01::evaluate(io::stdin().lock(), &params)
+            } else {
+                open_input(&source).and_then(|reader| day01::evaluate(reader, &params))
+            };
+            match evaluation {
+                Ok(evaluation) => {
+                    println!("Day 01 part 1: {}", evaluation.part1);
+                    println!("Day 01 part 2: {}", evaluation.part2);
+                    println!("    {}", evaluation.throughput);
+                }
+                Err(error) => {
+                    eprintln!("Day 01: error in {}: {}", source, error);
+                    failures += 1;
+                }
+            }
+        }
+        Command::Render { input, params, width, delay, svg } => {
+            let source = input.unwrap_or_else(|| default_input("input", 1, "b"));
+            let (config, frames) = load_input(&source)
+                .and_then(|input| day01::render::day01_frames(&input, &params))
+                .map_err(|error| format!("error in {}: {}", source, error))?;
+            for frame in &frames {
+                println!("{}", frame.ascii(width));
+                if let Some(delay) = delay {
+                    thread::sleep(Duration::from_millis(delay));
+                }
+            }
+            if let Some(filename) = svg {
+                fs::write(&filename, day01::render::svg(&frames, config)).map_err(|error| format!("cannot write {}: {}", filename, error))?;
+                println!("drew {} steps to {}", frames.len(), filename);
+            }
+        }
+        Command::Report { part, rule, base, input, limit } => {
+            let source = input.unwrap_or_else(|| default_input("input", 2, "b"));
+            let input = load_input(&source).map_err(|error| format!("cannot read {}: {}", source, error))?;
+            // a rule of its own is reported once, under the part if one is given
+            let rules = match (part, rule) {
+                (_, Some(rule)) => vec![(part.unwrap_or(Part::One), rule)],
+                (Some(part), None) => vec![(part, if part == Part::One { Rule::PART1 } else { Rule::PART2 })],
+                (None, None) => vec![(Part::One, Rule::PART1), (Part::Two, Rule::PART2)],
+            };
+            for (part, rule) in rules {
+                match day02::day02_breakdown(&input, rule, base, Some(limit)) {
+                    Ok(reports) => {
+                        println!("Day 02 part {} ({}, base {}):", part, rule, base);
+                        for report in reports {
+                            println!("{}", report);
+                        }
+                    }
+                    Err(error) => {
+                        eprintln!("Day 02 part {}: error in {}: {}", part, source, error);
+                        failures += 1;
+                    }
+                }
+            }
+        }
+        Command::Inverse { part, target, input, params, max_edits } => {
+            let source = input.unwrap_or_else(|| default_input("input", 1, "b"));
+            let edits = load_input(&source).and_then(|input| day01::day01_inverse(&input, &params, part, target, max_edits));
+            match edits {
+                Ok(Some(edits)) => {
+                    println!("Day 01 part {}: {} after {} edits", part, target, edits.len());
+                    for edit in edits {
+                        println!("    {}", edit);
+                    }
+                }
+                Ok(None) => {
+                    eprintln!("Day 01 part {}: {} takes more than {} edits", part, target, max_edits);
+                    failures += 1;
+                }
+                Err(error) => {
+                    eprintln!("Day 01: error in {}: {}", source, error);
+                    failures += 1;
+                }
+            }
+        }
+        Command::Bench(options) => failures += run_bench(options)?,
+        Command::New { day, title } => {
+            let created = scaffold(Path::new("."), day, &title).map_err(|error| error.to_string())?;
+            for path in created {
+                println!("created {}", path.display());
+            }
+            println!("registered day {:02} in src/lib.rs", day);
+        }
+        Command::Fetch { day, server, session, input_dir } => {
+            let client = Client::new(&server, &session, &input_dir).map_err(|error| error.to_string())?;
+            match client.fetch_input(day) {
+                Ok((_, true)) => println!("downloaded {}", client.input_path(day).display()),
+                Ok((_, false)) => println!("already cached in {}", client.input_path(day).display()),
+                Err(error) => {
+                    eprintln!("Day {:02}: cannot fetch the input: {}", day, error);
+                    failures += 1;
+                }
+            }
+        }
+        Command::Submit { day, part, answer, server, session, input_dir } => {
+            let client = Client::new(&server, &session, &input_dir).map_err(|error| error.to_string())?;
+            // without an explicit answer, solve the (downloaded) real input first
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    let solution = find(day).ok_or_else(|| format!("day {} is not implemented", day))?;
+                    let (input, _) = client.fetch_input(day).map_err(|error| error.to_string())?;
+                    let result = solution.run(part, &input).ok_or_else(|| format!("day {} part {} is not solved", day, part))?;
+                    result.map_err(|error| error.to_string())?
+                }
+            };
+            match client.submit(day, part, &answer) {
+                Ok(Verdict::Correct) => println!("Day {:02} part {}: {} is correct", day, part, answer),
+                Ok(verdict) => {
+                    println!("Day {:02} part {}: {} was not accepted: {:?}", day, part, answer, verdict);
+                    failures += 1;
+                }
+                Err(error) => {
+                    eprintln!("Day {:02} part {}: cannot submit: {}", day, part, error);
+                    failures += 1;
+                }
+            }
+        }
+        Command::Serve { port, session, answers, input_dir } => {
+            let manifest = Manifest::load(&answers).map_err(|error| format!("cannot load {}: {}", answers, error))?;
+            let puzzles = Puzzles::from_files(&session, &manifest, &input_dir).map_err(|error| error.to_string())?;
+            let server = MockServer::start(&format!("127.0.0.1:{}", port), puzzles).map_err(|error| error.to_string())?;
+            println!("serving {} puzzle inputs on {}", input_dir, server.url());
+            server.wait();
+        }
+    }
+    Ok(failures)
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let result = parse_args(&args).and_then(execute);
+    match result {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_run_with_options() {
+        let command = parse_args(&args("run 4 --part 2 --input input/day04a.txt")).unwrap();
+        let expected = Command::Run {
+            day: 4,
+            part: Some(Part::Two),
+            input: Some("input/day04a.txt".to_string()),
+            params: Params::default(),
+            json: false,
+            trace: Trace::default(),
+        };
+        assert_eq!(command, expected);
+        let command = parse_args(&args("run 4 --json")).unwrap();
+        assert_eq!(command, Command::Run { day: 4, part: None, input: None, params: Params::default(), json: true, trace: Trace::default() });
+    }
+
+    #[test]
+    fn parse_run_with_params() {
+        let command = parse_args(&args("run 1 --set size=10,start=0")).unwrap();
+        let params = "size=10,start=0".parse::<Params>().unwrap();
+        assert_eq!(command, Command::Run { day: 1, part: None, input: None, params, json: false, trace: Trace::default() });
+        assert!(parse_args(&args("run 1 --set size")).is_err());
+    }
+
+    #[test]
+    fn parse_all_with_defaults() {
+        let command = parse_args(&args("all")).unwrap();
+        assert_eq!(
+            command,
+            Command::All { input_dir: "input".to_string(), suffix: "b".to_string(), json: false, trace: Trace::default() }
+        );
+    }
+
+    #[test]
+    fn parse_trace_options() {
+        let command = parse_args(&args("run 1 --verbosity detail --trace-file day01.log")).unwrap();
+        let trace = Trace { verbosity: Some(Verbosity::Detail), file: Some("day01.log".to_string()) };
+        assert_eq!(command, Command::Run { day: 1, part: None, input: None, params: Params::default(), json: false, trace });
+        let command = parse_args(&args("all --verbosity 1")).unwrap();
+        let trace = Trace { verbosity: Some(Verbosity::Steps), file: None };
+        assert_eq!(command, Command::All { input_dir: "input".to_string(), suffix: "b".to_string(), json: false, trace });
+        assert!(parse_args(&args("run 1 --verbosity loud")).is_err());
+    }
+
+    #[test]
+    fn parse_verify_for_one_day() {
+        let command = parse_args(&args("verify --day 5 --answers other.txt")).unwrap();
+        assert_eq!(command, Command::Verify { answers: "other.txt".to_string(), input_dir: "input".to_string(), day: Some(5) });
+    }
+
+    #[test]
+    fn parse_stream() {
+        let command = parse_args(&args("stream --input - --set start=0")).unwrap();
+        let params = "start=0".parse::<Params>().unwrap();
+        assert_eq!(command, Command::Stream { input: Some("-".to_string()), params });
+        assert_eq!(parse_args(&args("stream")).unwrap(), Command::Stream { input: None, params: Params::default() });
+        assert!(parse_args(&args("stream 1")).is_err());
+    }
+
+    #[test]
+    fn parse_render() {
+        let command = parse_args(&args("render --width 40 --svg day01.svg")).unwrap();
+        let expected = Command::Render { input: None, params: Params::default(), width: 40, delay: None, svg: Some("day01.svg".to_string()) };
+        assert_eq!(command, expected);
+        assert!(parse_args(&args("render --delay soon")).is_err());
+    }
+
+    #[test]
+    fn parse_report() {
+        let command = parse_args(&args("report --part 2 --limit 3")).unwrap();
+        assert_eq!(command, Command::Report { part: Some(Part::Two), rule: None, base: Base::DECIMAL, input: None, limit: 3 });
+        assert_eq!(parse_args(&args("report")).unwrap(), Command::Report { part: None, rule: None, base: Base::DECIMAL, input: None, limit: 10 });
+        let command = parse_args(&args("report --rule x3-4 --base 16")).unwrap();
+        let expected = Command::Report { part: None, rule: Some(Rule::Repeated { min: 3, max: 4 }), base: Base::new(16).unwrap(), input: None, limit: 10 };
+        assert_eq!(command, expected);
+        assert!(parse_args(&args("report --rule x1")).is_err());
+        assert!(parse_args(&args("report --base 37")).is_err());
+    }
+
+    #[test]
+    fn parse_inverse() {
+        let command = parse_args(&args("inverse 2 100 --max-edits 5")).unwrap();
+        let expected = Command::Inverse { part: Part::Two, target: 100, input: None, params: Params::default(), max_edits: 5 };
+        assert_eq!(command, expected);
+        assert!(parse_args(&args("inverse 1")).is_err());
+        assert!(parse_args(&args("inverse 1 many")).is_err());
+    }
+
+    #[test]
+    fn parse_bench() {
+        let command = parse_args(&args("bench --day 2 --runs 5 --baseline bench.txt --tolerance 25")).unwrap();
+        let expected = Bench {
+            day: Some(2),
+            part: None,
+            runs: 5,
+            warmup: 2,
+            input_dir: "input".to_string(),
+            suffix: "b".to_string(),
+            save: None,
+            baseline: Some("bench.txt".to_string()),
+            tolerance: 25.0,
+        };
+        assert_eq!(command, Command::Bench(expected));
+        assert!(parse_args(&args("bench --runs many")).is_err());
+    }
+
+    #[test]
+    fn parse_submit() {
+        let command = parse_args(&args("submit 3 2 --answer 42 --session abc --server http://localhost:9000")).unwrap();
+        assert_eq!(
+            command,
+            Command::Submit {
+                day: 3,
+                part: Part::Two,
+                answer: Some("42".to_string()),
+                server: "http://localhost:9000".to_string(),
+                session: "abc".to_string(),
+                input_dir: "input".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert!(parse_args(&args("")).is_err());
+        assert!(parse_args(&args("run")).is_err());
+        assert!(parse_args(&args("run x")).is_err());
+        assert!(parse_args(&args("run 1 --part 3")).is_err());
+        assert!(parse_args(&args("run 1 --part")).is_err());
+        assert!(parse_args(&args("list now")).is_err());
+        assert!(parse_args(&args("submit 1")).is_err());
+        assert!(parse_args(&args("dance")).is_err());
+    }
+}
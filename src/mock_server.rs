@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::answers::Manifest;
+use crate::client::YEAR;
+use crate::{AocError, Part};
+
+// the largest request body read, an answer form is a few dozen bytes
+pub const MAX_BODY: usize = 64 * 1024;
+
+// what the stand-in server knows: one account (session), its inputs and the right answers
+pub struct Puzzles {
+    pub session: String,
+    pub inputs: HashMap<u8, String>,
+    pub answers: HashMap<(u8, Part), String>,
+    pub cooldown: Duration,
+}
+
+impl Puzzles {
+    pub fn new(session: &str) -> Self {
+        Self { session: session.to_string(), inputs: HashMap::new(), answers: HashMap::new(), cooldown: Duration::from_secs(60) }
+    }
+
+    // serve the real inputs (dayNNb.txt) of the input directory, with the answers recorded for them: a day without an
+    // input is not served, unless the manifest has answers for it, and an input that cannot be read is an error
+    pub fn from_files(session: &str, manifest: &Manifest, input_dir: &str) -> Result<Self, AocError> {
+        let mut puzzles = Self::new(session);
+        for entry in manifest.entries().iter().filter(|entry| entry.input == format!("day{:02}b.txt", entry.day)) {
+            puzzles.answers.insert((entry.day, entry.part), entry.answer.clone());
+        }
+        for day in 1..=25 {
+            let path = Path::new(input_dir).join(format!("day{:02}b.txt", day));
+            match fs::read_to_string(&path) {
+                Ok(input) => {
+                    puzzles.inputs.insert(day, input);
+                }
+                Err(error) if error.kind() == ErrorKind::NotFound && !puzzles.answers.keys().any(|(answered, _)| *answered == day) => {}
+                Err(error) => return Err(AocError::domain(format!("cannot read {}: {}", path.display(), error))),
+            }
+        }
+        Ok(puzzles)
+    }
+}
+
+struct State {
+    puzzles: Puzzles,
+    last_submission: Option<Instant>,
+}
+
+pub struct MockServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    // bind to "127.0.0.1:0" to get any free port
+    pub fn start(bind: &str, puzzles: Puzzles) -> Result<Self, AocError> {
+        let listener = TcpListener::bind(bind)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let state = Mutex::new(State { puzzles, last_submission: None });
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                // connections are served one by one, that is plenty for a single client
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let _ = handle_connection(stream, &state);
+                    }
+                }
+            }
+        });
+        Ok(Self { addr, stop, handle: Some(handle) })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // serve until the process is killed
+    pub fn wait(mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // wake up the accepting thread, so it can notice it should stop
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// a value of an application/x-www-form-urlencoded form, with '+' for a space and %XX for any byte
+fn form_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let hex = value.get(idx + 1..idx + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[idx], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                idx += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn respond(stream: &mut TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        _ => "Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+fn handle_connection(mut stream: TcpStream, state: &Mutex<State>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    // the request line, then the headers until an empty line, then the body
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut session = None;
+    let mut length = Some(0);
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("cookie") {
+                session = value
+                    .split(';')
+                    .find_map(|cookie| cookie.trim().strip_prefix("session="))
+                    .map(str::to_string);
+            } else if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    // the body is only read once its length is known to be reasonable
+    let length = match length {
+        Some(length) if length <= MAX_BODY => length,
+        Some(_) => return respond(&mut stream, 413, "Request body too large."),
+        None => return respond(&mut stream, 400, "Invalid Content-Length."),
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+
+    let mut request = request_line.split_whitespace();
+    let (method, path) = (request.next().unwrap_or(""), request.next().unwrap_or(""));
+    let route = path
+        .strip_prefix(&format!("/{}/day/", YEAR))
+        .and_then(|rest| rest.split_once('/'))
+        .and_then(|(day, action)| Some((day.parse::<u8>().ok()?, action)));
+
+    let mut state = state.lock().unwrap();
+    if session.as_deref() != Some(state.puzzles.session.as_str()) {
+        return respond(&mut stream, 400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.");
+    }
+    match (method, route) {
+        ("GET", Some((day, "input"))) => match state.puzzles.inputs.get(&day) {
+            Some(input) => {
+                let input = input.clone();
+                respond(&mut stream, 200, &input)
+            }
+            None => respond(&mut stream, 404, "Please don't repeatedly request this endpoint before it unlocks!"),
+        },
+        ("POST", Some((day, "answer"))) => {
+            let form = body
+                .split('&')
+                .filter_map(|field| field.split_once('='))
+                .map(|(name, value)| (form_decode(name), form_decode(value)))
+                .collect::<HashMap<String, String>>();
+            let part = form.get("level").and_then(|level| level.parse::<Part>().ok());
+            let answer = form.get("answer").map_or("", String::as_str);
+            if state.last_submission.is_some_and(|last| last.elapsed() < state.puzzles.cooldown) {
+                return respond(&mut stream, 200, "You gave an answer too recently; you have to wait after submitting an answer before trying again.");
+            }
+            state.last_submission = Some(Instant::now());
+            let page = match part.and_then(|part| state.puzzles.answers.get(&(day, part))) {
+                Some(expected) if expected == answer => "That's the right answer!",
+                Some(_) => "That's not the right answer.",
+                None => "You don't seem to be solving the right level.  Did you already complete it?",
+            };
+            respond(&mut stream, 200, page)
+        }
+        _ => respond(&mut stream, 404, "404 Not Found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the raw response of the server to a request
+    fn exchange(server: &MockServer, request: &str) -> String {
+        let mut stream = TcpStream::connect(server.addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn puzzles_from_files() {
+        let manifest = Manifest::parse("1 1 day01b.txt 3\n1 1 day01a.txt 9\n2 2 day02b.txt 5").unwrap();
+        let dir = std::env::temp_dir().join(format!("aoc_puzzles_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01b.txt"), "L68\n").unwrap();
+        fs::write(dir.join("day03b.txt"), "987\n").unwrap();
+        // day 2 has an answer but no input
+        let error = Puzzles::from_files("secret", &manifest, &dir.to_string_lossy()).err().unwrap();
+        assert!(error.to_string().starts_with(&format!("cannot read {}: ", dir.join("day02b.txt").display())));
+        fs::write(dir.join("day02b.txt"), "11-22\n").unwrap();
+        let puzzles = Puzzles::from_files("secret", &manifest, &dir.to_string_lossy()).unwrap();
+        assert_eq!(puzzles.inputs.len(), 3);
+        assert_eq!(puzzles.answers, HashMap::from([((1, Part::One), "3".to_string()), ((2, Part::Two), "5".to_string())]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn oversized_bodies_are_refused() {
+        let server = MockServer::start("127.0.0.1:0", Puzzles::new("secret")).unwrap();
+        let head = "POST /2025/day/1/answer HTTP/1.1\r\nCookie: session=secret\r\n";
+        let response = exchange(&server, &format!("{}Content-Length: {}\r\n\r\n", head, MAX_BODY + 1));
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+        let response = exchange(&server, &format!("{}Content-Length: 99999999999999999999999\r\n\r\n", head));
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        let response = exchange(&server, &format!("{}Content-Length: 15\r\n\r\nlevel=1&answer=", head));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
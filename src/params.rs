@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::AocError;
+
+// named settings of a run (e.g. the size of the dial of day 1), given on the command line as "key=value,key=value"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn parsed<T: FromStr>(&self, key: &str) -> Result<Option<T>, AocError> {
+        match self.get(key) {
+            Some(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| AocError::domain(format!("invalid value for {}: {}", key, value))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    // the values of `other` win over the own ones, as those of the command line over those of an input's header
+    pub fn merged(&self, other: &Params) -> Params {
+        let mut values = self.values.clone();
+        values.extend(other.values.clone());
+        Params { values }
+    }
+}
+
+impl FromStr for Params {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut params = Params::default();
+        for assignment in s.split(',').filter(|assignment| !assignment.trim().is_empty()) {
+            match assignment.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => params.set(key.trim(), value.trim()),
+                _ => return Err(format!("expected key=value, found {:?}", assignment)),
+            }
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_parse_and_get() {
+        let params = "size=100, start=5".parse::<Params>().unwrap();
+        assert_eq!(params.get("size"), Some("100"));
+        assert_eq!(params.parsed::<i16>("start").unwrap(), Some(5));
+        assert_eq!(params.parsed::<i16>("end").unwrap(), None);
+        assert_eq!(params.keys().collect::<Vec<&str>>(), vec!["size", "start"]);
+        assert!("size".parse::<Params>().is_err());
+        assert!("=5".parse::<Params>().is_err());
+        assert_eq!(params.parsed::<u8>("size").unwrap(), Some(100));
+        assert_eq!(params.parsed::<bool>("size").err().unwrap().to_string(), "invalid value for size: 100");
+    }
+
+    #[test]
+    fn params_merged() {
+        let header = "size=10,start=5".parse::<Params>().unwrap();
+        let command_line = "start=0".parse::<Params>().unwrap();
+        let merged = header.merged(&command_line);
+        assert_eq!((merged.get("size"), merged.get("start")), (Some("10"), Some("0")));
+    }
+}
@@ -0,0 +1,127 @@
+// randomized property checks for the tests, std-only: a seedable generator, a case runner and shrinking
+use std::env;
+use std::fmt::Debug;
+
+// splitmix64, small and good enough to spread test cases, every failure is replayed from its seed
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // a number in lo..=hi
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        let span = (hi as i128 - lo as i128 + 1) as u128;
+        (lo as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
+// the simpler variants of a failing case, tried in order while shrinking
+pub trait Shrink: Clone {
+    fn shrink(&self) -> Vec<Self>;
+}
+
+// towards zero: zero itself, half of it, one less
+pub fn shrink_towards_zero(value: i64) -> Vec<i64> {
+    let mut candidates = Vec::new();
+    for candidate in [0, value / 2, value - value.signum()] {
+        if candidate != value && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+// the seed can be changed through AOC_SEED, to look further than the fixed one
+pub fn seed(default: u64) -> u64 {
+    env::var("AOC_SEED").ok().and_then(|seed| seed.parse().ok()).unwrap_or(default)
+}
+
+// keep taking the first simpler variant that still fails, until none does
+pub fn shrink_failure<T: Shrink>(mut case: T, mut message: String, property: impl Fn(&T) -> Result<(), String>) -> (T, String) {
+    'shrinking: loop {
+        for candidate in case.shrink() {
+            if let Err(candidate_message) = property(&candidate) {
+                case = candidate;
+                message = candidate_message;
+                continue 'shrinking;
+            }
+        }
+        return (case, message);
+    }
+}
+
+// the first of `cases` generated cases the property fails for, shrunk to a minimal one
+pub fn find_failure<T: Shrink>(
+    seed: u64,
+    cases: usize,
+    generate: impl Fn(&mut Rng) -> T,
+    property: impl Fn(&T) -> Result<(), String>,
+) -> Option<(T, String)> {
+    let mut rng = Rng::new(seed);
+    for _ in 0..cases {
+        let case = generate(&mut rng);
+        if let Err(message) = property(&case) {
+            return Some(shrink_failure(case, message, property));
+        }
+    }
+    None
+}
+
+pub fn check<T: Shrink + Debug>(seed: u64, cases: usize, generate: impl Fn(&mut Rng) -> T, property: impl Fn(&T) -> Result<(), String>) {
+    if let Some((case, message)) = find_failure(seed, cases, generate, property) {
+        panic!("property failed (seed {}) for {:?}: {}", seed, case, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Pair(i64, i64);
+
+    impl Shrink for Pair {
+        fn shrink(&self) -> Vec<Self> {
+            let mut candidates = shrink_towards_zero(self.0).into_iter().map(|a| Pair(a, self.1)).collect::<Vec<Pair>>();
+            candidates.extend(shrink_towards_zero(self.1).into_iter().map(|b| Pair(self.0, b)));
+            candidates
+        }
+    }
+
+    #[test]
+    fn rng_is_reproducible_and_in_range() {
+        let (mut first, mut second) = (Rng::new(7), Rng::new(7));
+        for _ in 0..1000 {
+            let value = first.range(-3, 3);
+            assert_eq!(value, second.range(-3, 3));
+            assert!((-3..=3).contains(&value));
+        }
+        // the whole range does not overflow
+        Rng::new(1).range(i64::MIN, i64::MAX);
+    }
+
+    #[test]
+    fn failures_are_shrunk_to_the_boundary() {
+        let property = |pair: &Pair| if pair.0 + pair.1 < 100 { Ok(()) } else { Err(format!("{} is too much", pair.0 + pair.1)) };
+        let (case, message) = find_failure(1, 100, |rng| Pair(rng.range(0, 1000), rng.range(0, 1000)), property).unwrap();
+        assert_eq!(case.0 + case.1, 100);
+        assert_eq!(message, "100 is too much");
+        assert!(find_failure(1, 100, |rng| Pair(rng.range(0, 10), rng.range(0, 10)), property).is_none());
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use crate::{AnySolution, Params, Part};
+
+// the outcome of running one part of one day against one input
+#[derive(Debug)]
+pub struct RunResult {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+    pub answer: Option<String>,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+impl RunResult {
+    pub fn failed(day: u8, part: Part, input: &str, error: impl ToString) -> Self {
+        Self { day, part, input: input.to_string(), answer: None, elapsed: Duration::ZERO, error: Some(error.to_string()) }
+    }
+
+    // a single line of JSON, so that a whole run is a stream of JSON lines
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"part\":{},\"input\":{},\"answer\":{},\"elapsed_us\":{},\"error\":{}}}",
+            self.day,
+            self.part,
+            json_string(&self.input),
+            self.answer.as_deref().map_or("null".to_string(), json_string),
+            self.elapsed.as_micros(),
+            self.error.as_deref().map_or("null".to_string(), json_string),
+        )
+    }
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for char in text.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            char if (char as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", char as u32)),
+            char => escaped.push(char),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+pub fn run_timed(solution: &dyn AnySolution, part: Part, input_name: &str, input: &str, params: &Params) -> RunResult {
+    let start = Instant::now();
+    let result = solution.run_with(part, input, params);
+    let elapsed = start.elapsed();
+    let (answer, error) = match result {
+        Some(Ok(answer)) => (Some(answer), None),
+        Some(Err(error)) => (None, Some(error.to_string())),
+        // the part is not solved (yet), which is not an error
+        None => (None, None),
+    };
+    RunResult { day: solution.day(), part, input: input_name.to_string(), answer, elapsed, error }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find;
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(json_string("input/day01a.txt"), "\"input/day01a.txt\"");
+        assert_eq!(json_string("a \"b\"\\\n\u{1}"), "\"a \\\"b\\\"\\\\\\n\\u0001\"");
+    }
+
+    #[test]
+    fn run_result_as_json() {
+        let result = RunResult {
+            day: 1,
+            part: Part::Two,
+            input: "input/day01a.txt".to_string(),
+            answer: Some("6".to_string()),
+            elapsed: Duration::from_micros(1500),
+            error: None,
+        };
+        assert_eq!(result.to_json(), "{\"day\":1,\"part\":2,\"input\":\"input/day01a.txt\",\"answer\":\"6\",\"elapsed_us\":1500,\"error\":null}");
+        let result = RunResult::failed(4, Part::One, "-", "empty grid");
+        assert_eq!(result.to_json(), "{\"day\":4,\"part\":1,\"input\":\"-\",\"answer\":null,\"elapsed_us\":0,\"error\":\"empty grid\"}");
+    }
+
+    #[test]
+    fn run_timed_reports_errors() {
+        let solution = find(6).unwrap();
+        let result = run_timed(solution, Part::One, "inline", "1 2\n+ -", &Params::default());
+        assert_eq!(result.answer, None);
+        assert_eq!(result.error.as_deref(), Some("line 2, column 3: invalid operation: -"));
+        let result = run_timed(solution, Part::One, "inline", "1 2\n3 4\n+ *", &Params::default());
+        assert_eq!(result.answer.as_deref(), Some("12"));
+        let result = run_timed(solution, Part::Two, "inline", "1 2\n3 4\n+ *", &Params::default());
+        assert!(result.answer.is_none() && result.error.is_none());
+    }
+}
@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::AocError;
+
+// the module of a new day, following the same layout as the existing ones
+fn module_template(day: u8, title: &str) -> String {
+    let template = r#"use std::io::BufRead;
+
+use crate::{open_input, read_input, AocError, Solution};
+
+pub fn dayNN_part1_from_str(input: &str) -> Result<u64, AocError> {
+    // split the input into lines
+    let _lines = input.lines().collect::<Vec<&str>>();
+    Err(AocError::domain("day DAY part 1 is not solved yet"))
+}
+
+pub fn dayNN_part1_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    dayNN_part1_from_str(&read_input(reader)?)
+}
+
+pub fn dayNN_part1(filename: &str) -> Result<u64, AocError> {
+    dayNN_part1_from_reader(open_input(filename)?)
+}
+
+pub fn dayNN_part2_from_str(input: &str) -> Result<u64, AocError> {
+    // split the input into lines
+    let _lines = input.lines().collect::<Vec<&str>>();
+    Err(AocError::domain("day DAY part 2 is not solved yet"))
+}
+
+pub fn dayNN_part2_from_reader<R: BufRead>(reader: R) -> Result<u64, AocError> {
+    dayNN_part2_from_str(&read_input(reader)?)
+}
+
+pub fn dayNN_part2(filename: &str) -> Result<u64, AocError> {
+    dayNN_part2_from_reader(open_input(filename)?)
+}
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    type Answer = u64;
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "TITLE"
+    }
+
+    fn part1(&self, input: &str) -> Result<Self::Answer, AocError> {
+        dayNN_part1_from_str(input)
+    }
+
+    fn part2(&self, input: &str) -> Option<Result<Self::Answer, AocError>> {
+        Some(dayNN_part2_from_str(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::recorded;
+    use crate::Part;
+
+    // each test is skipped until its answer is recorded in answers.txt
+
+    #[test]
+    fn dayNN_part1_a() {
+        let Some(expected) = recorded(DAY, Part::One, "dayNNa.txt") else {
+            return;
+        };
+        let result = dayNN_part1("input/dayNNa.txt").unwrap();
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[test]
+    fn dayNN_part1_b() {
+        let Some(expected) = recorded(DAY, Part::One, "dayNNb.txt") else {
+            return;
+        };
+        let result = dayNN_part1("input/dayNNb.txt").unwrap();
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[test]
+    fn dayNN_part2_a() {
+        let Some(expected) = recorded(DAY, Part::Two, "dayNNa.txt") else {
+            return;
+        };
+        let result = dayNN_part2("input/dayNNa.txt").unwrap();
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[test]
+    fn dayNN_part2_b() {
+        let Some(expected) = recorded(DAY, Part::Two, "dayNNb.txt") else {
+            return;
+        };
+        let result = dayNN_part2("input/dayNNb.txt").unwrap();
+        assert_eq!(result.to_string(), expected);
+    }
+}
+"#;
+    template
+        .replace("dayNN", &format!("day{:02}", day))
+        .replace("DayNN", &format!("Day{:02}", day))
+        .replace("DAY", &day.to_string())
+        .replace("TITLE", &title.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// insert `line` next to the lines for which `entry_day` finds a day, keeping them ordered by that day
+fn insert_ordered(text: &str, line: &str, entry_day: impl Fn(&str) -> Option<u8>, day: u8) -> Result<String, AocError> {
+    let lines = text.lines().collect::<Vec<&str>>();
+    let entries = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| entry_day(line).map(|entry| (idx, entry)))
+        .collect::<Vec<(usize, u8)>>();
+    if entries.iter().any(|(_, entry)| *entry == day) {
+        return Err(AocError::domain(format!("day {} is already registered", day)));
+    }
+    let idx = match entries.iter().rev().find(|(_, entry)| *entry < day) {
+        Some((idx, _)) => idx + 1,
+        None => match entries.first() {
+            Some((idx, _)) => *idx,
+            None => return Err(AocError::domain(format!("cannot find where to register {:?}", line.trim()))),
+        },
+    };
+    let mut result = lines[..idx].join("\n");
+    if idx > 0 {
+        result.push('\n');
+    }
+    result.push_str(line);
+    result.push('\n');
+    result.push_str(&lines[idx..].join("\n"));
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+fn mod_day(line: &str) -> Option<u8> {
+    line.strip_prefix("pub mod day")?.strip_suffix(";")?.parse().ok()
+}
+
+fn registry_day(line: &str) -> Option<u8> {
+    line.trim().strip_prefix("&day")?.split_once("::")?.0.parse().ok()
+}
+
+// create the module, its example and real inputs, and register it in lib.rs; returns the created files
+pub fn scaffold(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>, AocError> {
+    if day == 0 || day > 25 {
+        return Err(AocError::domain(format!("invalid day: {}", day)));
+    }
+    let module = root.join("src").join(format!("day{:02}.rs", day));
+    if module.exists() {
+        return Err(AocError::domain(format!("{} already exists", module.display())));
+    }
+
+    // prepare the registration before writing anything, so a failure leaves the tree untouched
+    let lib = root.join("src").join("lib.rs");
+    let text = fs::read_to_string(&lib)?;
+    let text = insert_ordered(&text, &format!("pub mod day{:02};", day), mod_day, day)?;
+    let text = insert_ordered(&text, &format!("    &day{:02}::Day{:02},", day, day), registry_day, day)?;
+
+    let mut created = Vec::new();
+    fs::write(&module, module_template(day, title))?;
+    created.push(module);
+    fs::create_dir_all(root.join("input"))?;
+    for suffix in ["a", "b"] {
+        let input = root.join("input").join(format!("day{:02}{}.txt", day, suffix));
+        if !input.exists() {
+            fs::write(&input, "")?;
+            created.push(input);
+        }
+    }
+    fs::write(&lib, text)?;
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod answers;\npub mod day01;\npub mod day03;\npub mod error;\n\nstatic SOLUTIONS: &[&dyn AnySolution] = &[\n    &day01::Day01,\n    &day03::Day03,\n];\n";
+
+    #[test]
+    fn scaffold_registers_in_order() {
+        let text = insert_ordered(LIB, "pub mod day02;", mod_day, 2).unwrap();
+        let text = insert_ordered(&text, "    &day02::Day02,", registry_day, 2).unwrap();
+        assert_eq!(text, "pub mod answers;\npub mod day01;\npub mod day02;\npub mod day03;\npub mod error;\n\nstatic SOLUTIONS: &[&dyn AnySolution] = &[\n    &day01::Day01,\n    &day02::Day02,\n    &day03::Day03,\n];\n");
+        let text = insert_ordered(LIB, "pub mod day07;", mod_day, 7).unwrap();
+        assert!(text.contains("pub mod day03;\npub mod day07;\npub mod error;"));
+    }
+
+    #[test]
+    fn scaffold_rejects_registered_day() {
+        let error = insert_ordered(LIB, "pub mod day03;", mod_day, 3).err().unwrap();
+        assert_eq!(error.to_string(), "day 3 is already registered");
+    }
+
+    #[test]
+    fn scaffold_creates_the_files() {
+        let root = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src").join("lib.rs"), LIB).unwrap();
+
+        let created = scaffold(&root, 7, "Laboratories").unwrap();
+        assert_eq!(created.len(), 3);
+        let module = fs::read_to_string(root.join("src").join("day07.rs")).unwrap();
+        assert!(module.contains("pub fn day07_part1(filename: &str) -> Result<u64, AocError> {"));
+        assert!(module.contains("\"Laboratories\""));
+        assert!(module.contains("let Some(expected) = recorded(7, Part::Two, \"day07b.txt\") else {"));
+        assert_eq!(fs::read_to_string(root.join("input").join("day07a.txt")).unwrap(), "");
+        let lib = fs::read_to_string(root.join("src").join("lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day07;") && lib.contains("&day07::Day07,"));
+        assert!(scaffold(&root, 7, "Laboratories").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::rc::Rc;
+use std::str::FromStr;
+
+// how much the solvers tell about what they are doing, nothing by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    // one event per step: a dial turn, a removal round, a range merge
+    Steps,
+    // the steps, plus whole intermediate states (e.g. the grid after every round)
+    Detail,
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verbosity::Quiet => write!(f, "quiet"),
+            Verbosity::Steps => write!(f, "steps"),
+            Verbosity::Detail => write!(f, "detail"),
+        }
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quiet" | "0" => Ok(Verbosity::Quiet),
+            "steps" | "1" => Ok(Verbosity::Steps),
+            "detail" | "2" => Ok(Verbosity::Detail),
+            _ => Err(format!("Invalid verbosity: {}", s)),
+        }
+    }
+}
+
+pub trait TraceSink {
+    fn event(&mut self, message: &str);
+}
+
+pub struct StdoutSink;
+
+impl TraceSink for StdoutSink {
+    fn event(&mut self, message: &str) {
+        println!("{}", message);
+    }
+}
+
+// keeps stdout to the output of the run, as the JSON lines of --json
+pub struct StderrSink;
+
+impl TraceSink for StderrSink {
+    fn event(&mut self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+pub struct FileSink {
+    writer: BufWriter<File>,
+}
+
+impl FileSink {
+    pub fn create(filename: &str) -> io::Result<Self> {
+        Ok(Self { writer: BufWriter::new(File::create(filename)?) })
+    }
+}
+
+impl TraceSink for FileSink {
+    fn event(&mut self, message: &str) {
+        // tracing must never break a run, a failing write only loses the event
+        let _ = writeln!(self.writer, "{}", message);
+    }
+}
+
+// keeps the events in memory, the handle can be cloned to read them afterwards
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    events: Rc<RefCell<Vec<String>>>,
+}
+
+impl MemorySink {
+    pub fn events(&self) -> Vec<String> {
+        self.events.borrow().clone()
+    }
+}
+
+impl TraceSink for MemorySink {
+    fn event(&mut self, message: &str) {
+        self.events.borrow_mut().push(message.to_string());
+    }
+}
+
+struct Tracer {
+    verbosity: Verbosity,
+    sink: Box<dyn TraceSink>,
+}
+
+thread_local! {
+    static TRACER: RefCell<Option<Tracer>> = const { RefCell::new(None) };
+}
+
+// run `f` with the events of the given verbosity (or lower) going to the sink
+pub fn with_tracer<T>(verbosity: Verbosity, sink: Box<dyn TraceSink>, f: impl FnOnce() -> T) -> T {
+    let previous = TRACER.with(|tracer| tracer.replace(Some(Tracer { verbosity, sink })));
+    let result = f();
+    TRACER.with(|tracer| tracer.replace(previous));
+    result
+}
+
+pub fn enabled(level: Verbosity) -> bool {
+    TRACER.with(|tracer| tracer.borrow().as_ref().is_some_and(|tracer| level <= tracer.verbosity))
+}
+
+// the message is only built when somebody listens
+pub fn emit(level: Verbosity, message: impl FnOnce() -> String) {
+    if level == Verbosity::Quiet || !enabled(level) {
+        return;
+    }
+    let message = message();
+    TRACER.with(|tracer| {
+        if let Some(tracer) = tracer.borrow_mut().as_mut() {
+            tracer.sink.event(&message);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_respects_verbosity() {
+        let sink = MemorySink::default();
+        with_tracer(Verbosity::Steps, Box::new(sink.clone()), || {
+            emit(Verbosity::Steps, || "step".to_string());
+            emit(Verbosity::Detail, || panic!("not built"));
+            assert!(enabled(Verbosity::Steps));
+            assert!(!enabled(Verbosity::Detail));
+        });
+        assert_eq!(sink.events(), vec!["step"]);
+    }
+
+    #[test]
+    fn trace_is_off_outside_of_a_run() {
+        emit(Verbosity::Steps, || panic!("not built"));
+        assert!(!enabled(Verbosity::Steps));
+    }
+
+    #[test]
+    fn trace_verbosity_parse() {
+        assert_eq!("detail".parse::<Verbosity>().unwrap(), Verbosity::Detail);
+        assert_eq!("1".parse::<Verbosity>().unwrap(), Verbosity::Steps);
+        assert!("loud".parse::<Verbosity>().is_err());
+    }
+}